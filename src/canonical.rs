@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+
+use crate::{
+    formatter::{format_file, FormatOptions},
+    kv::{KeyValue, KeyValueFile, Value},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyCase {
+    #[default]
+    Preserve,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalOptions {
+    pub sort_keys: bool,
+    pub normalize_numbers: bool,
+    pub key_case: KeyCase,
+    pub line_ending: LineEnding,
+    pub format: FormatOptions,
+}
+
+impl Default for CanonicalOptions {
+    fn default() -> Self {
+        CanonicalOptions {
+            sort_keys: true,
+            normalize_numbers: true,
+            key_case: KeyCase::Preserve,
+            line_ending: LineEnding::Lf,
+            format: FormatOptions::default(),
+        }
+    }
+}
+
+pub fn canonicalize(value: &mut Value, options: &CanonicalOptions) {
+    match value {
+        Value::Value(v) => {
+            if options.normalize_numbers {
+                if let Some(number) = normalize_number(v) {
+                    *v = number;
+                }
+            }
+        }
+        Value::Section(section) => canonicalize_kvs(section, options),
+    }
+}
+
+// sorting is stable, so repeated keys keep their relative order
+pub fn canonicalize_kvs(kvs: &mut [KeyValue], options: &CanonicalOptions) {
+    for kv in kvs.iter_mut() {
+        match options.key_case {
+            KeyCase::Preserve => (),
+            KeyCase::Lower => kv.key = kv.key.to_lowercase(),
+            KeyCase::Upper => kv.key = kv.key.to_uppercase(),
+        }
+
+        canonicalize(&mut kv.value, options);
+    }
+
+    if options.sort_keys {
+        kvs.sort_by(|a, b| natural_cmp(&a.key, &b.key));
+    }
+}
+
+pub fn to_canonical_string(file: &KeyValueFile, options: &CanonicalOptions) -> String {
+    let mut file = file.clone();
+
+    canonicalize_kvs(&mut file.kvs, options);
+
+    let format = FormatOptions {
+        newline: match options.line_ending {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+        .to_string(),
+        ..options.format.clone()
+    };
+
+    format_file(&file, &format)
+}
+
+// digit runs are compared by their numeric value, so "2" < "10" and "item_9" < "item_10"
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (is_digits(a), is_digits(b)) {
+                (true, true) => {
+                    let a_trimmed = a.trim_start_matches('0');
+                    let b_trimmed = b.trim_start_matches('0');
+
+                    a_trimmed
+                        .len()
+                        .cmp(&b_trimmed.len())
+                        .then_with(|| a_trimmed.cmp(b_trimmed))
+                }
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (false, false) => a.cmp(b),
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn chunks(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let digits = first.is_ascii_digit();
+
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+
+        Some(chunk)
+    })
+}
+
+fn is_digits(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit())
+}
+
+// integers lose leading zeros and plus signs, decimals their trailing zeros, only when the
+// shorter text is exactly the same number, so "3.14159265358979323846" isn't rounded to an f64
+// and exponents like "1e300" are left alone instead of written out
+fn normalize_number(text: &str) -> Option<String> {
+    let numeric = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+        && text.contains(|c: char| c.is_ascii_digit());
+
+    if !numeric {
+        return None;
+    }
+
+    if let Ok(n) = text.parse::<i64>() {
+        return Some(n.to_string());
+    }
+
+    if let Ok(n) = text.parse::<u64>() {
+        return Some(n.to_string());
+    }
+
+    // integers too large for u64 would lose digits as a float
+    let decimal = shortest_decimal(text)?;
+
+    text.parse::<f64>()
+        .ok()
+        .map(|n| n.to_string())
+        .filter(|n| *n == decimal)
+}
+
+// "+01.50" as "1.5", the same number without any digit that doesn't change it
+fn shortest_decimal(text: &str) -> Option<String> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (int, fraction) = unsigned.split_once('.')?;
+
+    if !int
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let int = match int.trim_start_matches('0') {
+        "" => "0",
+        int => int,
+    };
+
+    match fraction.trim_end_matches('0') {
+        "" => Some(format!("{}{}", sign, int)),
+        fraction => Some(format!("{}{}.{}", sign, int, fraction)),
+    }
+}
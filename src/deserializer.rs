@@ -1,23 +1,112 @@
-use std::collections::VecDeque;
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::{HashMap, VecDeque},
+    fmt,
+    marker::PhantomData,
+    rc::Rc,
+    str::FromStr,
+};
 
 use serde::{
-    de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess},
+    de::{
+        self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    },
     Deserialize,
 };
 
 use crate::{
     error::Error,
-    kv::{KeyValue, Value},
-    parser::{parse_file, parse_input},
+    kv::{KeyPath, KeyValue, Value},
+    parser::{
+        key_matches, parse_file_spanned, parse_input_spanned, Location, SpannedKeyValue,
+        SpannedValue,
+    },
+    with::{split_tokens, PIPE_SEPARATED, SPACE_SEPARATED},
 };
 
+mod lenient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SequenceLayout {
+    // keys are the indices "0", "1", ... in any order
+    #[default]
+    Indexed,
+    // keys are the indices "1", "2", ... in any order
+    OneBased,
+    // keys are ignored
+    DocumentOrder,
+    // every key is the same, e.g. "Game" "a" "Game" "b"
+    RepeatedKey,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScalarMode {
+    // str::parse, bools are "0" or "1"
+    #[default]
+    Standard,
+    // what the engine accepts: "1.5f", " 42 ", "0x1F", "12abc", "true", "yes"
+    Lenient,
+    // like Standard without "inf" and "nan", errors carry the rejected text
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariantMatching {
+    #[default]
+    Exact,
+    IgnoreCase,
+    // "MELEE_ATTACK" matches MeleeAttack
+    IgnoreCaseAndUnderscores,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializerOptions {
+    pub sequence: SequenceLayout,
+    // makes deserialize_any report numbers and "true"/"false" instead of strings,
+    // "0" and "1" stay numbers
+    pub infer_scalars: bool,
+    pub scalars: ScalarMode,
+    pub variants: VariantMatching,
+    // stripped from enum variant names before matching, e.g. "DOTA_UNIT_CAP_"
+    pub variant_prefixes: Vec<String>,
+    // "" is None for Option<T>, otherwise it's Some("") and only a missing key is None
+    pub empty_as_none: bool,
+    // from_*_validating gives up after this many errors, as every error costs another pass
+    pub max_errors: usize,
+}
+
+impl Default for DeserializerOptions {
+    fn default() -> Self {
+        DeserializerOptions {
+            sequence: SequenceLayout::Indexed,
+            infer_scalars: false,
+            scalars: ScalarMode::Standard,
+            variants: VariantMatching::Exact,
+            variant_prefixes: vec![],
+            empty_as_none: true,
+            max_errors: 100,
+        }
+    }
+}
+
 pub fn from_file<'a, T>(path: &'a str) -> Result<T, Error>
 where
     T: Deserialize<'a>,
 {
-    let parsed = parse_file(path)?;
+    from_file_with_options(path, DeserializerOptions::default())
+}
 
-    let mut deserializer = Deserializer::from_kv(Value::Section(parsed));
+pub fn from_file_with_options<'a, T>(
+    path: &'a str,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let (parsed, spans, _) = read_spanned(path)?;
+
+    let mut deserializer = Deserializer::with_spans(Value::Section(parsed), options, spans);
     let t = T::deserialize(&mut deserializer)?;
 
     Ok(t)
@@ -27,53 +116,656 @@ pub fn from_str<'a, T>(input: &'a str) -> Result<T, Error>
 where
     T: Deserialize<'a>,
 {
-    let parsed = parse_input(input)?;
+    from_str_with_options(input, DeserializerOptions::default())
+}
+
+pub fn from_str_with_options<'a, T>(
+    input: &'a str,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let parsed = parse_input_spanned(input)?;
+    let spans = Spans::from_kvs(&parsed.kvs);
+
+    let mut deserializer =
+        Deserializer::with_spans(Value::Section(parsed.to_file().kvs), options, spans);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+// for files wrapped in a single root key like "DOTAAbilities" { ... }, T is read from its body
+pub fn from_file_rooted<'a, T>(path: &'a str, root: Option<&str>) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_file_rooted_with_options(path, root, DeserializerOptions::default())
+}
+
+pub fn from_file_rooted_with_options<'a, T>(
+    path: &'a str,
+    root: Option<&str>,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let (parsed, spans, roots) = read_spanned(path)?;
+
+    let mut deserializer = Deserializer::rooted(parsed, spans, roots, root, options)?;
+    let t = T::deserialize(&mut deserializer)?;
+
+    Ok(t)
+}
+
+pub fn from_str_rooted<'a, T>(input: &'a str, root: Option<&str>) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_str_rooted_with_options(input, root, DeserializerOptions::default())
+}
+
+pub fn from_str_rooted_with_options<'a, T>(
+    input: &'a str,
+    root: Option<&str>,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let parsed = parse_input_spanned(input)?;
+    let spans = Spans::from_kvs(&parsed.kvs);
+    let roots = parsed.kvs.len();
+
+    let mut deserializer = Deserializer::rooted(parsed.to_file().kvs, spans, roots, root, options)?;
+    let t = T::deserialize(&mut deserializer)?;
+
+    Ok(t)
+}
+
+// deserializes only the value at a path like "DOTAAbilities/item_blink", keys match in any
+// case like they do for the engine and every occurrence of a repeated key is searched
+pub fn from_str_at<'a, T>(input: &'a str, path: &str) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_str_at_with_options(input, path, DeserializerOptions::default())
+}
+
+pub fn from_str_at_with_options<'a, T>(
+    input: &'a str,
+    path: &str,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let path = KeyPath::from(path);
+
+    if path.is_empty() {
+        return from_str_with_options(input, options);
+    }
+
+    let parsed = parse_input_spanned(input)?;
+    let spans = Spans::from_kvs(&parsed.kvs);
+    let own = parsed.kvs.len();
+
+    let mut deserializer = Deserializer::resolve(parsed.to_file().kvs, spans, own, path, options)?;
+    let t = T::deserialize(&mut deserializer)?;
+
+    Ok(t)
+}
+
+// the path is found like from_str_at finds it, in the file and its #base files read as one tree
+// the way from_file_rooted reads them: the body of a #base root is appended to the file's root
+// of the same name in any case
+pub fn from_file_at<'a, T>(path: &'a str, key_path: &str) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_file_at_with_options(path, key_path, DeserializerOptions::default())
+}
+
+pub fn from_file_at_with_options<'a, T>(
+    path: &'a str,
+    key_path: &str,
+    options: DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let key_path = KeyPath::from(key_path);
+
+    if key_path.is_empty() {
+        return from_file_with_options(path, options);
+    }
+
+    let (parsed, spans, own) = read_spanned(path)?;
 
-    let mut deserializer = Deserializer::from_kv(Value::Section(parsed.kvs));
+    let mut deserializer = Deserializer::resolve(parsed, spans, own, key_path, options)?;
     let t = T::deserialize(&mut deserializer)?;
+
     Ok(t)
 }
 
-pub struct Deserializer {
+// the kvs of #base files that repeat a root section of the file are moved into it
+fn merge_roots(parsed: Vec<KeyValue>, spans: Spans, own: usize) -> (Vec<KeyValue>, Vec<Rc<Spans>>) {
+    let mut kvs: Vec<KeyValue> = vec![];
+    let mut merged: Vec<Rc<Spans>> = vec![];
+
+    for (i, (kv, kv_spans)) in parsed.into_iter().zip(spans.children).enumerate() {
+        let root = (i >= own)
+            .then(|| {
+                kvs[..own].iter().position(|root| {
+                    root.key.eq_ignore_ascii_case(&kv.key) && root.value.is_section()
+                })
+            })
+            .flatten();
+
+        match (root, kv.value) {
+            (Some(root), Value::Section(mut base)) => {
+                if let Value::Section(section) = &mut kvs[root].value {
+                    section.append(&mut base);
+                    Rc::make_mut(&mut merged[root]).extend(section);
+                }
+            }
+            (_, value) => {
+                kvs.push(KeyValue { key: kv.key, value });
+                merged.push(kv_spans);
+            }
+        }
+    }
+
+    (kvs, merged)
+}
+
+// same search as parser::parse_input_at
+fn find_value<'a>(
+    kvs: &'a [KeyValue],
+    spans: &[Rc<Spans>],
+    path: &[String],
+) -> Option<(&'a Value, Rc<Spans>)> {
+    let (first, rest) = path.split_first()?;
+
+    kvs.iter()
+        .zip(spans)
+        .filter(|(kv, _)| key_matches(&kv.key, first))
+        .find_map(|(kv, spans)| match (&kv.value, rest.is_empty()) {
+            (value, true) => Some((value, spans.clone())),
+            (Value::Section(section), false) => find_value(section, &spans.children, rest),
+            (Value::Value(_), false) => None,
+        })
+}
+
+// also returns how many of the kvs come from the file itself
+fn read_spanned(path: &str) -> Result<(Vec<KeyValue>, Spans, usize), Error> {
+    let (file, mut bases) = parse_file_spanned(path)?;
+
+    // positions are only known for the file itself, its kvs come before the ones of #base files
+    let mut spans = Spans::from_kvs(&file.kvs);
+    let own = spans.children.len();
+
+    let mut parsed = file.to_file().kvs;
+    parsed.append(&mut bases);
+
+    spans.extend(&parsed);
+
+    Ok((parsed, spans, own))
+}
+
+pub fn from_file_validating<'a, T>(
+    path: &'a str,
+    options: DeserializerOptions,
+) -> Result<(Option<T>, Vec<Error>), Error>
+where
+    T: Deserialize<'a>,
+{
+    let (parsed, spans, _) = read_spanned(path)?;
+
+    Ok(validate(Value::Section(parsed), options, spans))
+}
+
+pub fn from_str_validating<'a, T>(
+    input: &'a str,
+    options: DeserializerOptions,
+) -> Result<(Option<T>, Vec<Error>), Error>
+where
+    T: Deserialize<'a>,
+{
+    let parsed = parse_input_spanned(input)?;
+    let spans = Spans::from_kvs(&parsed.kvs);
+
+    Ok(validate(
+        Value::Section(parsed.to_file().kvs),
+        options,
+        spans,
+    ))
+}
+
+// every failed entry is skipped and the whole value deserialized again, so fields with
+// #[serde(default)] get their default and a missing required field fails its parent in turn,
+// serde can't go on after an error so n errors take n + 1 passes, capped by max_errors, and the
+// passes borrow the parsed value instead of copying it
+fn validate<'a, T>(
     input: Value,
+    options: DeserializerOptions,
+    spans: Spans,
+) -> (Option<T>, Vec<Error>)
+where
+    T: Deserialize<'a>,
+{
+    let options = Rc::new(options);
+    let spans = Rc::new(spans);
+
+    let mut errors = vec![];
+    let mut skipped = 0;
+
+    loop {
+        let mut deserializer = Deserializer {
+            input: Cow::Borrowed(&input),
+            options: options.clone(),
+            path: KeyPath::default(),
+            spans: Some(spans.clone()),
+        };
+
+        match T::deserialize(&mut deserializer).map_err(|e| deserializer.wrap(e)) {
+            Ok(t) => return (Some(t), errors),
+            Err(_) if spans.take_derived() => (),
+            Err(e) => errors.push(e),
+        }
+
+        if errors.len() >= options.max_errors {
+            return (None, errors);
+        }
+
+        // nothing left to skip when the root itself failed
+        let now_skipped = spans.skipped_count();
+
+        if now_skipped == skipped {
+            return (None, errors);
+        }
+
+        skipped = now_skipped;
+    }
 }
 
-impl Deserializer {
+// source positions of a value and its children, in the shape of the Value tree
+#[derive(Debug, Clone, Default)]
+struct Spans {
+    location: Option<Location>,
+    // set for entries that failed to deserialize, see validate
+    skipped: Cell<bool>,
+    // set when the entry failed only because a skipped child is missing
+    derived: Cell<bool>,
+    children: Vec<Rc<Spans>>,
+}
+
+impl Spans {
+    fn from_kvs(kvs: &[SpannedKeyValue]) -> Spans {
+        Spans {
+            children: kvs.iter().map(|kv| Rc::new(Spans::from_kv(kv))).collect(),
+            ..Default::default()
+        }
+    }
+
+    // entries without a position, like the ones coming from #base files
+    fn from_value(value: &Value) -> Spans {
+        let mut spans = Spans::default();
+
+        if let Value::Section(section) = value {
+            spans.extend(section);
+        }
+
+        spans
+    }
+
+    fn extend(&mut self, kvs: &[KeyValue]) {
+        for kv in kvs.iter().skip(self.children.len()) {
+            self.children.push(Rc::new(Spans::from_value(&kv.value)));
+        }
+    }
+
+    // clears the flag everywhere, only one entry fails per pass
+    fn take_derived(&self) -> bool {
+        let mut derived = self.derived.take();
+
+        for child in &self.children {
+            derived |= child.take_derived();
+        }
+
+        derived
+    }
+
+    fn skipped_count(&self) -> usize {
+        let children: usize = self.children.iter().map(|c| c.skipped_count()).sum();
+        children + self.skipped.get() as usize
+    }
+
+    fn from_kv(kv: &SpannedKeyValue) -> Spans {
+        let mut spans = match &kv.value {
+            SpannedValue::Section(section) => Spans::from_kvs(section),
+            SpannedValue::Value(_) => Spans::default(),
+        };
+
+        spans.location = Some(kv.key_span.start);
+        spans
+    }
+}
+
+// a child of a section
+struct Entry<'a> {
+    key: String,
+    value: Cow<'a, Value>,
+    spans: Option<Rc<Spans>>,
+}
+
+impl<'a> Entry<'a> {
+    fn is_skipped(&self) -> bool {
+        self.spans.as_ref().is_some_and(|spans| spans.skipped.get())
+    }
+
+    fn deserializer(self, parent: &KeyPath, options: &Rc<DeserializerOptions>) -> Deserializer<'a> {
+        Deserializer {
+            path: parent.join(&self.key),
+            input: self.value,
+            options: options.clone(),
+            spans: self.spans,
+        }
+    }
+}
+
+// errors keep the path of the innermost value they came from
+fn with_path(error: Error, path: &KeyPath, location: Option<Location>) -> Error {
+    match error {
+        Error::KeyPathError { .. } => error,
+        _ if path.is_empty() => error,
+        _ => Error::KeyPathError {
+            path: path.clone(),
+            location,
+            error: Box::new(error),
+        },
+    }
+}
+
+pub struct Deserializer<'a> {
+    // borrowed from the parent section, owned at the root
+    input: Cow<'a, Value>,
+    // shared by every nested deserializer
+    options: Rc<DeserializerOptions>,
+    path: KeyPath,
+    spans: Option<Rc<Spans>>,
+}
+
+impl Deserializer<'_> {
     pub fn from_kv(input: Value) -> Self {
-        Deserializer { input }
+        Deserializer::with_options(input, DeserializerOptions::default())
+    }
+
+    pub fn with_options(input: Value, options: DeserializerOptions) -> Self {
+        Deserializer {
+            input: Cow::Owned(input),
+            options: Rc::new(options),
+            path: KeyPath::default(),
+            spans: None,
+        }
+    }
+
+    fn with_spans(input: Value, options: DeserializerOptions, spans: Spans) -> Self {
+        Deserializer {
+            spans: Some(Rc::new(spans)),
+            ..Deserializer::with_options(input, options)
+        }
+    }
+
+    // `roots` is the number of kvs of the file itself, #base files repeat the root and their
+    // bodies are appended to it
+    fn rooted(
+        kvs: Vec<KeyValue>,
+        spans: Spans,
+        roots: usize,
+        name: Option<&str>,
+        options: DeserializerOptions,
+    ) -> Result<Self, Error> {
+        if roots != 1 {
+            return Err(Error::ExpectedSingleRootError(roots));
+        }
+
+        let mut kvs = kvs.into_iter();
+        let root = kvs.next().unwrap();
+
+        // keys are case insensitive to the engine
+        if name.is_some_and(|name| !root.key.eq_ignore_ascii_case(name)) {
+            return Err(Error::UnexpectedRootError(root.key));
+        }
+
+        let mut input = root.value;
+
+        for kv in kvs {
+            if !kv.key.eq_ignore_ascii_case(&root.key) {
+                return Err(Error::UnexpectedRootError(kv.key));
+            }
+
+            match (&mut input, kv.value) {
+                (Value::Section(section), Value::Section(mut base)) => section.append(&mut base),
+                _ => return Err(Error::ExpectedSectionError),
+            }
+        }
+
+        Ok(Deserializer {
+            input: Cow::Owned(input),
+            options: Rc::new(options),
+            path: KeyPath::from(vec![root.key]),
+            spans: spans.children.first().cloned(),
+        })
+    }
+
+    fn at(input: Value, path: KeyPath, spans: Rc<Spans>, options: DeserializerOptions) -> Self {
+        Deserializer {
+            input: Cow::Owned(input),
+            options: Rc::new(options),
+            path,
+            spans: Some(spans),
+        }
+    }
+
+    // the value at `path` for from_str_at and from_file_at, `own` is the number of kvs that
+    // don't come from #base files
+    fn resolve(
+        kvs: Vec<KeyValue>,
+        spans: Spans,
+        own: usize,
+        path: KeyPath,
+        options: DeserializerOptions,
+    ) -> Result<Self, Error> {
+        let (kvs, spans) = merge_roots(kvs, spans, own);
+
+        let (value, spans) = find_value(&kvs, &spans, path.0.as_slice())
+            .ok_or_else(|| Error::PathNotFoundError(path.to_string()))?;
+
+        Ok(Deserializer::at(value.clone(), path, spans, options))
+    }
+
+    fn location(&self) -> Option<Location> {
+        self.spans.as_ref().and_then(|spans| spans.location)
+    }
+
+    // the first deserializer to see an error is the one that failed
+    fn wrap(&self, error: Error) -> Error {
+        if !matches!(error, Error::KeyPathError { .. }) {
+            if let Some(spans) = &self.spans {
+                // the root can't be skipped
+                if !self.path.is_empty() {
+                    spans.skipped.set(true);
+                }
+
+                spans.derived.set(self.is_missing_skipped(&error));
+            }
+        }
+
+        with_path(error, &self.path, self.location())
+    }
+
+    // a missing `a` after `a` was skipped repeats the error of `a`
+    fn is_missing_skipped(&self, error: &Error) -> bool {
+        let (Value::Section(section), Some(spans), Error::MissingFieldError(field)) =
+            (&*self.input, &self.spans, error)
+        else {
+            return false;
+        };
+
+        section
+            .iter()
+            .zip(&spans.children)
+            .any(|(kv, child)| child.skipped.get() && kv.key == *field)
+    }
+
+    fn entries<'b>(&self, kvs: &'b [KeyValue]) -> Vec<Entry<'b>> {
+        kvs.iter()
+            .enumerate()
+            .map(|(i, kv)| Entry {
+                key: kv.key.clone(),
+                value: Cow::Borrowed(&kv.value),
+                spans: self
+                    .spans
+                    .as_ref()
+                    .and_then(|spans| spans.children.get(i).cloned()),
+            })
+            .collect()
     }
 
     pub fn parse_value<T>(&self) -> Result<T, Error>
     where
         T: std::str::FromStr,
     {
-        if let Value::Value(v) = &self.input {
+        if let Value::Value(v) = &*self.input {
             v.parse().map_err(|_| Error::ExpectedValueError)
         } else {
             Err(Error::ExpectedValueError)
         }
     }
+
+    fn parse_int<T>(&self, expected: &'static str) -> Result<T, Error>
+    where
+        T: FromStr,
+    {
+        match (&*self.input, self.options.scalars) {
+            (Value::Value(v), ScalarMode::Lenient) => lenient::int(v)
+                .to_string()
+                .parse()
+                .map_err(|_| invalid_scalar(v, expected)),
+            (Value::Value(v), ScalarMode::Strict) => {
+                v.parse().map_err(|_| invalid_scalar(v, expected))
+            }
+            _ => self.parse_value(),
+        }
+    }
+
+    fn parse_float<T>(&self, expected: &'static str) -> Result<T, Error>
+    where
+        T: FromStr,
+    {
+        match (&*self.input, self.options.scalars) {
+            (Value::Value(v), ScalarMode::Lenient) => lenient::float(v)
+                .parse()
+                .map_err(|_| invalid_scalar(v, expected)),
+            (Value::Value(v), ScalarMode::Strict) => {
+                if v.contains(|c: char| c.is_ascii_alphabetic() && !matches!(c, 'e' | 'E')) {
+                    return Err(invalid_scalar(v, expected));
+                }
+
+                v.parse().map_err(|_| invalid_scalar(v, expected))
+            }
+            _ => self.parse_value(),
+        }
+    }
+
+    // names that match no variant are left alone for serde to report
+    fn resolve_variant<'a>(&self, name: &'a str, variants: &'static [&'static str]) -> &'a str {
+        let options = &self.options;
+
+        if options.variants == VariantMatching::Exact && options.variant_prefixes.is_empty() {
+            return name;
+        }
+
+        let ignore_case = options.variants != VariantMatching::Exact;
+
+        let stripped = options.variant_prefixes.iter().find_map(|prefix| {
+            name.get(..prefix.len())
+                .filter(|head| {
+                    if ignore_case {
+                        head.eq_ignore_ascii_case(prefix)
+                    } else {
+                        head == prefix
+                    }
+                })
+                .map(|_| &name[prefix.len()..])
+        });
+
+        for candidate in [Some(name), stripped].into_iter().flatten() {
+            if let Some(variant) = variants
+                .iter()
+                .find(|variant| variant_eq(variant, candidate, options.variants))
+            {
+                return variant;
+            }
+        }
+
+        name
+    }
 }
 
-impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
+fn variant_eq(variant: &str, name: &str, matching: VariantMatching) -> bool {
+    match matching {
+        VariantMatching::Exact => variant == name,
+        VariantMatching::IgnoreCase => variant.eq_ignore_ascii_case(name),
+        VariantMatching::IgnoreCaseAndUnderscores => {
+            let letters = |s: &str| {
+                s.chars()
+                    .filter(|&c| c != '_')
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect::<String>()
+            };
+
+            letters(variant) == letters(name)
+        }
+    }
+}
+
+fn invalid_scalar(text: &str, expected: &'static str) -> Error {
+    Error::InvalidScalarError {
+        text: text.to_string(),
+        expected,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        self.deserialize_map(visitor)
+        match &*self.input {
+            Value::Value(v) if self.options.infer_scalars => visit_inferred(v, visitor),
+            Value::Value(v) => visitor.visit_str(v),
+            Value::Section(_) => self.deserialize_map(visitor),
+        }
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
-            if v == "0" {
+        if let Value::Value(v) = &*self.input {
+            if self.options.scalars == ScalarMode::Lenient {
+                visitor.visit_bool(lenient::bool(v))
+            } else if v == "0" {
                 visitor.visit_bool(false)
             } else if v == "1" {
                 visitor.visit_bool(true)
+            } else if self.options.scalars == ScalarMode::Strict {
+                Err(invalid_scalar(v, "bool"))
             } else {
                 Err(Error::ParseBoolError)
             }
@@ -86,77 +778,77 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_i8(self.parse_value()?)
+        visitor.visit_i8(self.parse_int("i8")?)
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_i16(self.parse_value()?)
+        visitor.visit_i16(self.parse_int("i16")?)
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_i32(self.parse_value()?)
+        visitor.visit_i32(self.parse_int("i32")?)
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_i64(self.parse_value()?)
+        visitor.visit_i64(self.parse_int("i64")?)
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_u8(self.parse_value()?)
+        visitor.visit_u8(self.parse_int("u8")?)
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_u16(self.parse_value()?)
+        visitor.visit_u16(self.parse_int("u16")?)
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_u32(self.parse_value()?)
+        visitor.visit_u32(self.parse_int("u32")?)
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_u64(self.parse_value()?)
+        visitor.visit_u64(self.parse_int("u64")?)
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_f32(self.parse_value()?)
+        visitor.visit_f32(self.parse_float("f32")?)
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_f64(self.parse_value()?)
+        visitor.visit_f64(self.parse_float("f64")?)
     }
 
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
+        if let Value::Value(v) = &*self.input {
             if v.len() == 1 {
                 visitor.visit_char(v.chars().next().unwrap())
             } else {
@@ -171,7 +863,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
+        if let Value::Value(v) = &*self.input {
             visitor.visit_str(v)
         } else {
             Err(Error::ExpectedValueError)
@@ -182,7 +874,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
+        if let Value::Value(v) = &*self.input {
             visitor.visit_string(v.clone())
         } else {
             Err(Error::ExpectedValueError)
@@ -207,14 +899,9 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
-            if v.is_empty() {
-                visitor.visit_none()
-            } else {
-                visitor.visit_some(self)
-            }
-        } else {
-            Err(Error::ExpectedValueError)
+        match &*self.input {
+            Value::Value(v) if v.is_empty() && self.options.empty_as_none => visitor.visit_none(),
+            _ => visitor.visit_some(self),
         }
     }
 
@@ -222,7 +909,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
+        if let Value::Value(v) = &*self.input {
             if v.is_empty() {
                 visitor.visit_unit()
             } else {
@@ -246,12 +933,52 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
+        // a key that occurs once is a list of one
+        if name == REPEATED {
+            let mut parent = self.path.clone();
+            let key = parent.pop().unwrap_or_default();
+
+            return visitor.visit_seq(SectionSequence {
+                values: VecDeque::from([Entry {
+                    key,
+                    value: self.input.clone(),
+                    spans: self.spans.clone(),
+                }]),
+                path: parent,
+                options: self.options.clone(),
+            });
+        }
+
+        // a value of with::space_separated or with::pipe_separated, the tokens share its position
+        // so a bad one skips the whole value
+        if name == SPACE_SEPARATED || name == PIPE_SEPARATED {
+            let Value::Value(text) = &*self.input else {
+                return Err(Error::ExpectedValueError);
+            };
+
+            let values = split_tokens(name, text)
+                .into_iter()
+                .enumerate()
+                .map(|(i, token)| Entry {
+                    key: i.to_string(),
+                    value: Cow::Owned(Value::Value(token.to_string())),
+                    spans: self.spans.clone(),
+                })
+                .collect();
+
+            return visitor.visit_seq(SectionSequence {
+                values,
+                path: self.path.clone(),
+                options: self.options.clone(),
+            });
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -259,8 +986,12 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Section(v) = &self.input {
-            visitor.visit_seq(SectionSequence::new(v.clone()))
+        if let Value::Section(v) = &*self.input {
+            visitor.visit_seq(SectionSequence::new(
+                self.entries(v),
+                self.path.clone(),
+                self.options.clone(),
+            )?)
         } else {
             Err(Error::ExpectedSectionError)
         }
@@ -289,8 +1020,12 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Section(v) = &self.input {
-            visitor.visit_map(SectionMap::new(v.clone()))
+        if let Value::Section(v) = &*self.input {
+            visitor.visit_map(SectionMap::new(
+                self.entries(v),
+                self.path.clone(),
+                self.options.clone(),
+            ))
         } else {
             Err(Error::ExpectedSectionError)
         }
@@ -305,22 +1040,38 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        self.deserialize_map(visitor)
+        if let Value::Section(v) = &*self.input {
+            visitor.visit_map(SectionMap::grouped(
+                self.entries(v),
+                self.path.clone(),
+                self.options.clone(),
+            ))
+        } else {
+            Err(Error::ExpectedSectionError)
+        }
     }
 
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        if let Value::Value(v) = &self.input {
-            visitor.visit_enum(v.as_str().into_deserializer())
-        } else {
-            Err(Error::ExpectedValueError)
+        match &*self.input {
+            Value::Value(v) => {
+                visitor.visit_enum(self.resolve_variant(v, variants).into_deserializer())
+            }
+            // newtype, tuple and struct variants are written as { "Variant" ... }
+            Value::Section(section) => match self.entries(section).pop() {
+                Some(entry) if section.len() == 1 => visitor.visit_enum(SectionEnum {
+                    variant: self.resolve_variant(&entry.key, variants).to_string(),
+                    value: entry.deserializer(&self.path, &self.options),
+                }),
+                _ => Err(Error::ExpectedVariantError),
+            },
         }
     }
 
@@ -339,93 +1090,454 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer {
     }
 }
 
-struct SectionSequence {
-    kvs: Vec<KeyValue>,
-    current_index: usize,
+// only text a number prints back to exactly is inferred, so "007" and "1.50" stay strings
+// and deserializing into Value keeps the data as it was, like TypedValue::infer
+fn visit_inferred<'de, V>(v: &str, visitor: V) -> Result<V::Value, Error>
+where
+    V: de::Visitor<'de>,
+{
+    if let Some(n) = v.parse::<i64>().ok().filter(|n| n.to_string() == v) {
+        return visitor.visit_i64(n);
+    }
+
+    if let Some(n) = v.parse::<u64>().ok().filter(|n| n.to_string() == v) {
+        return visitor.visit_u64(n);
+    }
+
+    // keeps "inf" and "nan" as strings
+    if v.contains('.') {
+        if let Some(n) = v
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && n.to_string() == v)
+        {
+            return visitor.visit_f64(n);
+        }
+    }
+
+    match v {
+        "true" => visitor.visit_bool(true),
+        "false" => visitor.visit_bool(false),
+        _ => visitor.visit_str(v),
+    }
+}
+
+struct SectionSequence<'a> {
+    values: VecDeque<Entry<'a>>,
+    path: KeyPath,
+    options: Rc<DeserializerOptions>,
 }
 
-impl SectionSequence {
-    fn new(kvs: Vec<KeyValue>) -> SectionSequence {
-        let mut sorted_kvs = kvs;
+impl<'a> SectionSequence<'a> {
+    fn new(
+        entries: Vec<Entry<'a>>,
+        path: KeyPath,
+        options: Rc<DeserializerOptions>,
+    ) -> Result<Self, Error> {
+        let values = match options.sequence {
+            SequenceLayout::Indexed => Self::by_index(entries, 0)?,
+            SequenceLayout::OneBased => Self::by_index(entries, 1)?,
+            SequenceLayout::DocumentOrder => entries.into(),
+            SequenceLayout::RepeatedKey => {
+                if let Some(entry) = entries.iter().find(|e| e.key != entries[0].key) {
+                    return Err(Error::SequenceIndexError(entry.key.clone()));
+                }
+
+                entries.into()
+            }
+        };
+
+        // skipped entries still hold their index
+        let values = values.into_iter().filter(|e| !e.is_skipped()).collect();
+
+        Ok(SectionSequence {
+            values,
+            path,
+            options,
+        })
+    }
+
+    // indices are compared as numbers, so "10" comes after "9"
+    fn by_index(entries: Vec<Entry<'a>>, first: usize) -> Result<VecDeque<Entry<'a>>, Error> {
+        let mut slots: Vec<Option<Entry<'a>>> = entries.iter().map(|_| None).collect();
+
+        for entry in entries {
+            let index = entry
+                .key
+                .parse::<usize>()
+                .ok()
+                .filter(|&i| i >= first)
+                .ok_or_else(|| Error::SequenceIndexError(entry.key.clone()))?;
 
-        sorted_kvs.sort_by(|a, b| a.key.cmp(&b.key));
+            // an index past the end leaves a gap that is reported below
+            if let Some(slot) = slots.get_mut(index - first) {
+                if slot.is_some() {
+                    return Err(Error::SequenceIndexError(entry.key));
+                }
 
-        SectionSequence {
-            kvs: sorted_kvs,
-            current_index: 0,
+                *slot = Some(entry);
+            }
         }
+
+        slots
+            .into_iter()
+            .enumerate()
+            .map(|(i, slot)| slot.ok_or(Error::SequenceGapError(i + first)))
+            .collect()
     }
 }
 
-impl<'de> SeqAccess<'de> for SectionSequence {
+impl<'de> SeqAccess<'de> for SectionSequence<'_> {
     type Error = Error;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
     where
         T: DeserializeSeed<'de>,
     {
-        if self.current_index >= self.kvs.len() {
-            return Ok(None);
+        match self.values.pop_front() {
+            Some(entry) => {
+                let mut deserializer = entry.deserializer(&self.path, &self.options);
+
+                seed.deserialize(&mut deserializer)
+                    .map(Some)
+                    .map_err(|e| deserializer.wrap(e))
+            }
+            None => Ok(None),
         }
+    }
+}
 
-        let res = seed
-            .deserialize(&mut Deserializer {
-                input: self.kvs[self.current_index].value.clone(),
-            })
-            .map(Some);
+struct SectionMap<'a> {
+    // every occurrence of a key, more than one only for grouped maps
+    entries: VecDeque<Vec<Entry<'a>>>,
+    path: KeyPath,
+    options: Rc<DeserializerOptions>,
+}
+
+impl<'a> SectionMap<'a> {
+    fn new(entries: Vec<Entry<'a>>, path: KeyPath, options: Rc<DeserializerOptions>) -> Self {
+        SectionMap {
+            entries: entries
+                .into_iter()
+                .filter(|entry| !entry.is_skipped())
+                .map(|entry| vec![entry])
+                .collect(),
+            path,
+            options,
+        }
+    }
 
-        self.current_index += 1;
+    // repeated keys become a single entry at the position of their first occurrence
+    fn grouped(entries: Vec<Entry<'a>>, path: KeyPath, options: Rc<DeserializerOptions>) -> Self {
+        let mut groups: Vec<Vec<Entry<'a>>> = vec![];
+        let mut index: HashMap<String, usize> = HashMap::new();
 
-        res
+        for entry in entries.into_iter().filter(|entry| !entry.is_skipped()) {
+            match index.get(&entry.key) {
+                Some(&i) => groups[i].push(entry),
+                None => {
+                    index.insert(entry.key.clone(), groups.len());
+                    groups.push(vec![entry]);
+                }
+            }
+        }
+
+        SectionMap {
+            entries: groups.into(),
+            path,
+            options,
+        }
     }
 }
 
-struct SectionMap {
-    keys: VecDeque<String>,
-    values: VecDeque<Value>,
-}
+impl<'de> MapAccess<'de> for SectionMap<'_> {
+    type Error = Error;
 
-impl SectionMap {
-    fn new(kvs: Vec<KeyValue>) -> SectionMap {
-        let mut section_map = SectionMap {
-            keys: VecDeque::new(),
-            values: VecDeque::new(),
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let entry = match self.entries.front() {
+            Some(group) => &group[0],
+            None => return Ok(None),
         };
 
-        for kv in &kvs {
-            section_map.keys.push_back(kv.key.clone());
-            section_map.values.push_back(kv.value.clone());
+        let mut deserializer = Entry {
+            key: entry.key.clone(),
+            value: Cow::Owned(Value::Value(entry.key.clone())),
+            spans: entry.spans.clone(),
+        }
+        .deserializer(&self.path, &self.options);
+
+        seed.deserialize(&mut deserializer)
+            .map(Some)
+            .map_err(|e| deserializer.wrap(e))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let mut group = self.entries.pop_front().unwrap();
+
+        if group.len() > 1 {
+            let path = self.path.join(&group[0].key);
+            // the first duplicate
+            let location = group[1].spans.as_ref().and_then(|spans| spans.location);
+            let duplicates: Vec<Rc<Spans>> =
+                group[1..].iter().filter_map(|e| e.spans.clone()).collect();
+
+            return seed
+                .deserialize(Occurrences {
+                    entries: group,
+                    path: self.path.clone(),
+                    options: self.options.clone(),
+                })
+                .map_err(|e| {
+                    if !matches!(e, Error::KeyPathError { .. }) {
+                        duplicates.iter().for_each(|spans| spans.skipped.set(true));
+                    }
+
+                    with_path(e, &path, location)
+                });
         }
 
-        section_map
+        let mut deserializer = group.pop().unwrap().deserializer(&self.path, &self.options);
+
+        seed.deserialize(&mut deserializer)
+            .map_err(|e| deserializer.wrap(e))
     }
 }
 
-impl<'de> MapAccess<'de> for SectionMap {
+// a key repeated inside a struct, only Repeated may take it
+struct Occurrences<'a> {
+    entries: Vec<Entry<'a>>,
+    // of the section containing the key
+    path: KeyPath,
+    options: Rc<DeserializerOptions>,
+}
+
+impl<'de> de::Deserializer<'de> for Occurrences<'_> {
     type Error = Error;
 
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
-        K: DeserializeSeed<'de>,
+        V: de::Visitor<'de>,
     {
-        if self.keys.is_empty() {
-            return Ok(None);
+        Err(Error::Custom(format!(
+            "duplicate field `{}`",
+            self.entries[0].key
+        )))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name == REPEATED {
+            visitor.visit_seq(SectionSequence {
+                values: self.entries.into(),
+                path: self.path,
+                options: self.options,
+            })
+        } else {
+            self.deserialize_any(visitor)
         }
+    }
+
+    // unknown keys may repeat
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
 
-        let key = self.keys.pop_front().unwrap();
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier
+    }
+}
 
-        seed.deserialize(&mut Deserializer {
-            input: Value::Value(key),
-        })
-        .map(Some)
+const REPEATED: &str = "$valve_kv::Repeated";
+
+// collects every occurrence of a key inside a struct, in document order
+// a missing key needs #[serde(default)] to become an empty list
+// inside a #[serde(flatten)] struct serde hands the fields over one occurrence at a time,
+// so a single occurrence still works there but a repeated one is a duplicate field error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repeated<T>(pub Vec<T>);
+
+impl<T> Default for Repeated<T> {
+    fn default() -> Self {
+        Repeated(vec![])
     }
+}
 
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+impl<'de, T> Deserialize<'de> for Repeated<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct RepeatedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> de::Visitor<'de> for RepeatedVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Repeated<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a repeated key")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = vec![];
+
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+
+                Ok(Repeated(values))
+            }
+
+            // other formats see a plain list, a flattened KV field its only occurrence
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(OneOrMany(PhantomData))
+            }
+        }
+
+        struct OneOrMany<T>(PhantomData<T>);
+
+        impl<'de, T> de::Visitor<'de> for OneOrMany<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Repeated<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list or a single value")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Vec::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Repeated)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                T::deserialize(de::value::MapAccessDeserializer::new(map))
+                    .map(|t| Repeated(vec![t]))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(v.into_deserializer()).map(|t| Repeated(vec![t]))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(v.into_deserializer()).map(|t| Repeated(vec![t]))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(REPEATED, RepeatedVisitor(PhantomData))
+    }
+}
+
+// for #[serde(deserialize_with = "valve_kv::deserializer::repeated")] on a Vec<T> field
+pub fn repeated<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Repeated::deserialize(deserializer).map(|repeated| repeated.0)
+}
+
+struct SectionEnum<'a> {
+    variant: String,
+    value: Deserializer<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for SectionEnum<'a> {
+    type Error = Error;
+    type Variant = Deserializer<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
         V: DeserializeSeed<'de>,
     {
-        let value = self.values.pop_front().unwrap();
+        let mut deserializer = Deserializer {
+            input: Cow::Owned(Value::Value(self.variant)),
+            options: self.value.options.clone(),
+            path: self.value.path.clone(),
+            spans: self.value.spans.clone(),
+        };
 
-        seed.deserialize(&mut Deserializer { input: value })
+        let variant = seed
+            .deserialize(&mut deserializer)
+            .map_err(|e| deserializer.wrap(e))?;
+
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    // { "Variant" "" }
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match &*self.input {
+            Value::Value(v) if v.is_empty() => Ok(()),
+            Value::Value(_) => Err(Error::ExpectedUnitError),
+            Value::Section(_) => Err(Error::ExpectedValueError),
+        }
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut self).map_err(|e| self.wrap(e))
+    }
+
+    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(&mut self, visitor).map_err(|e| self.wrap(e))
+    }
+
+    fn struct_variant<V>(
+        mut self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(&mut self, visitor).map_err(|e| self.wrap(e))
     }
 }
@@ -0,0 +1,80 @@
+// the engine reads scalars with atoi/atof, so anything goes and trailing garbage is ignored
+
+// leading whitespace, an optional sign, then as many digits as there are, "0x" switches to hex
+pub(super) fn int(text: &str) -> i128 {
+    let text = text.trim_start();
+
+    let (negative, text) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+
+    let (radix, text) = match text.get(..2) {
+        Some("0x" | "0X") => (16, &text[2..]),
+        _ => (10, text),
+    };
+
+    let n = text
+        .chars()
+        .map_while(|c| c.to_digit(radix))
+        .fold(0i128, |n, digit| {
+            n.saturating_mul(i128::from(radix))
+                .saturating_add(i128::from(digit))
+        });
+
+    if negative {
+        -n
+    } else {
+        n
+    }
+}
+
+// the longest prefix that is a number, "1.5f" is 1.5 and "abc" is 0
+pub(super) fn float(text: &str) -> &str {
+    let text = text.trim_start();
+    let bytes = text.as_bytes();
+
+    let digits = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let start = end;
+
+    end = digits(end);
+    let mut mantissa = end - start;
+
+    if bytes.get(end) == Some(&b'.') {
+        let fraction = digits(end + 1);
+        mantissa += fraction - end - 1;
+        end = fraction;
+    }
+
+    if mantissa == 0 {
+        return "0";
+    }
+
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent = digits(sign);
+
+        if exponent > sign {
+            end = exponent;
+        }
+    }
+
+    &text[..end]
+}
+
+// "true" and "yes" count too, anything else is a number
+pub(super) fn bool(text: &str) -> bool {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" => true,
+        "false" | "no" => false,
+        _ => int(text) != 0,
+    }
+}
@@ -0,0 +1,335 @@
+use std::{collections::HashMap, collections::VecDeque, fs, path::Path, sync::Arc};
+
+use pest::iterators::Pair;
+
+use crate::{
+    error::Error,
+    kv::{KeyPath, KeyValue, Value},
+    parser::{parse_import, unquote, walk_input, PairVisitor, Rule},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(u32);
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+    // range in Document::text
+    Value { start: u32, len: u32 },
+    // range in Document::children
+    Section { start: u32, len: u32 },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    key: u32,
+    kind: NodeKind,
+}
+
+// each key is allocated once and shared by the lookup and the list, Arc keeps Document Send
+#[derive(Debug, Default)]
+struct Interner {
+    ids: HashMap<Arc<str>, u32>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&id) = self.ids.get(s) {
+            return id;
+        }
+
+        let id = self.strings.len() as u32;
+
+        let s: Arc<str> = s.into();
+
+        self.strings.push(s.clone());
+        self.ids.insert(s, id);
+
+        id
+    }
+
+    fn get(&self, s: &str) -> Option<u32> {
+        self.ids.get(s).copied()
+    }
+
+    fn resolve(&self, id: u32) -> &str {
+        &self.strings[id as usize]
+    }
+}
+
+// keys are interned and every node lives in a single arena, values are packed into one string buffer
+#[derive(Debug)]
+pub struct Document {
+    pub imports: Vec<String>,
+    keys: Interner,
+    text: String,
+    nodes: Vec<Node>,
+    children: Vec<u32>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        let mut keys = Interner::default();
+
+        Document {
+            imports: vec![],
+            nodes: vec![Node {
+                key: keys.intern(""),
+                kind: NodeKind::Section { start: 0, len: 0 },
+            }],
+            keys,
+            text: String::new(),
+            children: vec![],
+        }
+    }
+}
+
+impl Document {
+    pub fn parse(input: &str) -> Result<Document, Error> {
+        let mut document = Document::default();
+        let mut roots = vec![];
+
+        document.append_input(input, &mut roots)?;
+        document.set_roots(roots);
+
+        Ok(document)
+    }
+
+    pub fn from_file(path: &str) -> Result<Document, Error> {
+        let base_path = Path::new(path)
+            .parent()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+
+        let mut paths: VecDeque<String> = Default::default();
+        paths.push_back(path.to_string());
+
+        let mut document = Document::default();
+        let mut roots = vec![];
+
+        while let Some(current_path) = paths.pop_front() {
+            let file = String::from_utf8(fs::read(&current_path).map_err(Error::ReadFileError)?)
+                .map_err(Error::ReadUtf8Error)?;
+
+            let imports_start = document.imports.len();
+
+            document.append_input(&file, &mut roots)?;
+
+            for new_path in &document.imports[imports_start..] {
+                paths.push_back(format!("{}/{}", base_path, new_path));
+            }
+        }
+
+        document.set_roots(roots);
+
+        Ok(document)
+    }
+
+    pub fn from_kvs(kvs: &[KeyValue]) -> Document {
+        let mut document = Document::default();
+
+        let roots = kvs.iter().map(|kv| document.push_kv(kv)).collect();
+        document.set_roots(roots);
+
+        document
+    }
+
+    pub fn to_kvs(&self) -> Vec<KeyValue> {
+        self.root().children().map(|node| node.to_kv()).collect()
+    }
+
+    pub fn root(&self) -> NodeRef<'_> {
+        NodeRef {
+            document: self,
+            id: 0,
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> NodeRef<'_> {
+        NodeRef {
+            document: self,
+            id: id.0,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<NodeRef<'_>> {
+        self.root().get(key)
+    }
+
+    pub fn get_all<'a>(&'a self, key: &str) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        self.root().get_all(key)
+    }
+
+    pub fn lookup(&self, path: &str) -> Option<NodeRef<'_>> {
+        self.root().lookup(path)
+    }
+
+    // number of kvs in the document, the root excluded
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn append_input(&mut self, input: &str, roots: &mut Vec<u32>) -> Result<(), Error> {
+        let (imports, mut kvs) = walk_input(input, self)?;
+
+        self.imports.extend(imports.into_iter().map(parse_import));
+        roots.append(&mut kvs);
+
+        Ok(())
+    }
+
+    fn set_roots(&mut self, roots: Vec<u32>) {
+        self.nodes[0].kind = self.push_children(roots);
+    }
+
+    fn push_kv(&mut self, kv: &KeyValue) -> u32 {
+        let key = self.keys.intern(&kv.key);
+
+        let kind = match &kv.value {
+            Value::Value(value) => self.push_text(value),
+            Value::Section(section) => {
+                let children = section.iter().map(|kv| self.push_kv(kv)).collect();
+                self.push_children(children)
+            }
+        };
+
+        self.push_node(Node { key, kind })
+    }
+
+    fn push_node(&mut self, node: Node) -> u32 {
+        self.nodes.push(node);
+        (self.nodes.len() - 1) as u32
+    }
+
+    fn push_text(&mut self, value: &str) -> NodeKind {
+        let start = self.text.len() as u32;
+        self.text.push_str(value);
+
+        NodeKind::Value {
+            start,
+            len: value.len() as u32,
+        }
+    }
+
+    fn push_children(&mut self, children: Vec<u32>) -> NodeKind {
+        let start = self.children.len() as u32;
+        let len = children.len() as u32;
+
+        self.children.extend(children);
+
+        NodeKind::Section { start, len }
+    }
+}
+
+impl PairVisitor for Document {
+    type Output = u32;
+
+    fn visit_value(&mut self, _: &Pair<Rule>, key: &Pair<Rule>, value: &Pair<Rule>) -> u32 {
+        let key = self.keys.intern(unquote(key));
+        let kind = self.push_text(unquote(value));
+
+        self.push_node(Node { key, kind })
+    }
+
+    fn visit_section(
+        &mut self,
+        _: &Pair<Rule>,
+        key: &Pair<Rule>,
+        _: &Pair<Rule>,
+        children: Vec<u32>,
+    ) -> u32 {
+        let key = self.keys.intern(unquote(key));
+        let kind = self.push_children(children);
+
+        self.push_node(Node { key, kind })
+    }
+}
+
+impl From<&[KeyValue]> for Document {
+    fn from(value: &[KeyValue]) -> Self {
+        Document::from_kvs(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NodeRef<'a> {
+    document: &'a Document,
+    id: u32,
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn id(&self) -> NodeId {
+        NodeId(self.id)
+    }
+
+    pub fn key(&self) -> &'a str {
+        self.document.keys.resolve(self.node().key)
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.node().kind {
+            NodeKind::Value { start, len } => {
+                Some(&self.document.text[start as usize..(start + len) as usize])
+            }
+            NodeKind::Section { .. } => None,
+        }
+    }
+
+    pub fn is_section(&self) -> bool {
+        matches!(self.node().kind, NodeKind::Section { .. })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        let ids: &'a [u32] = match self.node().kind {
+            NodeKind::Value { .. } => &[],
+            NodeKind::Section { start, len } => {
+                &self.document.children[start as usize..(start + len) as usize]
+            }
+        };
+
+        let document = self.document;
+
+        ids.iter().map(move |&id| NodeRef { document, id })
+    }
+
+    pub fn get(&self, key: &str) -> Option<NodeRef<'a>> {
+        self.get_all(key).next()
+    }
+
+    pub fn get_all(&self, key: &str) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        // a key that was never interned can't be present in any section
+        let key = self.document.keys.get(key);
+
+        self.children()
+            .filter(move |node| Some(node.node().key) == key)
+    }
+
+    // follows the first occurrence of every key in a "a/b/c" path
+    pub fn lookup(&self, path: &str) -> Option<NodeRef<'a>> {
+        KeyPath::from(path)
+            .iter()
+            .try_fold(*self, |node, key| node.get(key))
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self.as_str() {
+            Some(value) => Value::Value(value.to_string()),
+            None => Value::Section(self.children().map(|node| node.to_kv()).collect()),
+        }
+    }
+
+    pub fn to_kv(&self) -> KeyValue {
+        KeyValue {
+            key: self.key().to_string(),
+            value: self.to_value(),
+        }
+    }
+
+    fn node(&self) -> Node {
+        self.document.nodes[self.id as usize]
+    }
+}
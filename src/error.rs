@@ -3,7 +3,10 @@ use std::{error, fmt::Display, string::FromUtf8Error};
 
 use serde::{de, ser};
 
-use crate::parser::Rule;
+use crate::{
+    kv::KeyPath,
+    parser::{Location, Rule},
+};
 
 #[derive(Debug)]
 pub enum Error {
@@ -14,14 +17,62 @@ pub enum Error {
     ExpectedUnitError,
     ExpectedCharError,
     ExpectedSectionError,
+    ExpectedVariantError,
     ParseBoolError,
+    ParseTypedValueError(String),
+    InvalidScalarError {
+        text: String,
+        expected: &'static str,
+    },
     SectionIsNotSequence,
+    SequenceIndexError(String),
+    SequenceGapError(usize),
+    // with the number of roots found
+    ExpectedSingleRootError(usize),
+    UnexpectedRootError(String),
+    PathNotFoundError(String),
+    InvalidPatchError(String),
+    InvalidSchemaError(String),
+    InvalidJsonError(String),
+    InvalidLintConfigError(String),
+    // a struct field without a value, by its serialized name
+    MissingFieldError(&'static str),
+    // a deserialization error and the value it happened at
+    KeyPathError {
+        path: KeyPath,
+        location: Option<Location>,
+        error: Box<Error>,
+    },
 
     Custom(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    pub fn path(&self) -> Option<&KeyPath> {
+        match self {
+            Error::KeyPathError { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Error::KeyPathError { location, .. } => *location,
+            _ => None,
+        }
+    }
+
+    // the error without its path
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::KeyPathError { error, .. } => error,
+            _ => self,
+        }
+    }
+}
+
 impl ser::Error for Error {
     fn custom<T>(msg: T) -> Self
     where
@@ -38,12 +89,24 @@ impl de::Error for Error {
     {
         Error::Custom(msg.to_string())
     }
+
+    fn missing_field(field: &'static str) -> Self {
+        Error::MissingFieldError(field)
+    }
 }
 
 impl error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_args!("{:?}", self))
+        match self {
+            Error::KeyPathError {
+                path,
+                location: Some(location),
+                error,
+            } => write!(f, "{} ({}): {}", path, location, error),
+            Error::KeyPathError { path, error, .. } => write!(f, "{}: {}", path, error),
+            _ => f.write_fmt(format_args!("{:?}", self)),
+        }
     }
 }
@@ -0,0 +1,178 @@
+use crate::{
+    kv::{KeyValue, KeyValueFile, Value},
+    serializer::escape,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BracePlacement {
+    // "key"
+    // {
+    #[default]
+    NextLine,
+    // "key" {
+    SameLine,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub indent: String,
+    // puts the values of a section into one column
+    pub align_values: bool,
+    // pads up to the value column with tabs instead of spaces
+    pub align_with_tabs: bool,
+    pub tab_width: usize,
+    // separates a top-level section from its neighbours with an empty line
+    pub blank_lines: bool,
+    pub braces: BracePlacement,
+    // ends every line, values keep whatever line breaks they contain
+    pub newline: String,
+}
+
+// matches the layout of Valve authored files
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: "\t".to_string(),
+            align_values: true,
+            align_with_tabs: true,
+            tab_width: 4,
+            blank_lines: true,
+            braces: BracePlacement::NextLine,
+            newline: "\n".to_string(),
+        }
+    }
+}
+
+pub fn format_file(file: &KeyValueFile, options: &FormatOptions) -> String {
+    let mut output = String::new();
+
+    for import in &file.imports {
+        output += &format!("#base \"{}\"{}", import, options.newline);
+    }
+
+    if !file.imports.is_empty() && !file.kvs.is_empty() {
+        output += &options.newline;
+    }
+
+    output + &format_kvs(&file.kvs, options)
+}
+
+pub fn format_kvs(kvs: &[KeyValue], options: &FormatOptions) -> String {
+    let mut formatter = Formatter {
+        options,
+        output: String::new(),
+    };
+
+    formatter.write_section(kvs, 0);
+    formatter.output
+}
+
+pub fn format_value(value: &Value, options: &FormatOptions) -> String {
+    match value {
+        Value::Value(v) => format!("{}{}", quote(v), options.newline),
+        Value::Section(section) => format_kvs(section, options),
+    }
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+    output: String,
+}
+
+impl Formatter<'_> {
+    fn write_section(&mut self, kvs: &[KeyValue], depth: usize) {
+        let indent = self.indent_width(depth);
+        let column = self.value_column(kvs, indent);
+
+        for (index, kv) in kvs.iter().enumerate() {
+            let around_section =
+                index > 0 && (kv.value.is_section() || kvs[index - 1].value.is_section());
+
+            if depth == 0 && self.options.blank_lines && around_section {
+                self.output += &self.options.newline;
+            }
+
+            self.write_indent(depth);
+
+            let key = quote(&kv.key);
+            self.output += &key;
+
+            match &kv.value {
+                Value::Value(v) => {
+                    self.write_padding(indent + key.chars().count(), column);
+                    self.output += &quote(v);
+                    self.output += &self.options.newline;
+                }
+                Value::Section(section) => {
+                    match self.options.braces {
+                        BracePlacement::NextLine => {
+                            self.output += &self.options.newline;
+                            self.write_indent(depth);
+                        }
+                        BracePlacement::SameLine => self.output += " ",
+                    }
+
+                    self.output += "{";
+                    self.output += &self.options.newline;
+                    self.write_section(section, depth + 1);
+                    self.write_indent(depth);
+                    self.output += "}";
+                    self.output += &self.options.newline;
+                }
+            }
+        }
+    }
+
+    // the column counts from the start of the line so tabs line up with the tab stops
+    fn value_column(&self, kvs: &[KeyValue], indent: usize) -> usize {
+        let widest = kvs
+            .iter()
+            .filter(|kv| !kv.value.is_section())
+            .map(|kv| quote(&kv.key).chars().count())
+            .max()
+            .unwrap_or(0)
+            + indent;
+
+        if self.options.align_with_tabs {
+            let tab_width = self.options.tab_width.max(1);
+            (widest / tab_width + 1) * tab_width
+        } else {
+            widest + 1
+        }
+    }
+
+    fn write_padding(&mut self, width: usize, column: usize) {
+        let tab_width = self.options.tab_width.max(1);
+
+        match (self.options.align_values, self.options.align_with_tabs) {
+            (true, true) => self.output += &"\t".repeat(column / tab_width - width / tab_width),
+            (true, false) => self.output += &" ".repeat(column - width),
+            (false, true) => self.output += "\t",
+            (false, false) => self.output += " ",
+        }
+    }
+
+    // how many columns the indentation of `depth` takes up
+    fn indent_width(&self, depth: usize) -> usize {
+        let tab_width = self.options.tab_width.max(1);
+
+        self.options
+            .indent
+            .repeat(depth)
+            .chars()
+            .fold(0, |column, c| match c {
+                '\t' => (column / tab_width + 1) * tab_width,
+                _ => column + 1,
+            })
+    }
+
+    fn write_indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.output += &self.options.indent;
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
@@ -0,0 +1,98 @@
+use serde_json::Map;
+
+use crate::{
+    error::Error,
+    kv::{KeyValue, Value},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonMode {
+    // sections become objects, duplicate keys collapse into the last occurrence
+    #[default]
+    Object,
+    // sections become arrays of [key, value] pairs, keeping duplicates and order
+    Lossless,
+}
+
+pub fn to_json(value: &Value, mode: JsonMode) -> serde_json::Value {
+    match (value, mode) {
+        (Value::Value(v), _) => serde_json::Value::String(v.clone()),
+        (Value::Section(section), JsonMode::Object) => {
+            let mut object = Map::new();
+
+            for kv in section {
+                object.insert(kv.key.clone(), to_json(&kv.value, mode));
+            }
+
+            serde_json::Value::Object(object)
+        }
+        (Value::Section(section), JsonMode::Lossless) => serde_json::Value::Array(
+            section
+                .iter()
+                .map(|kv| {
+                    serde_json::Value::Array(vec![
+                        serde_json::Value::String(kv.key.clone()),
+                        to_json(&kv.value, mode),
+                    ])
+                })
+                .collect(),
+        ),
+    }
+}
+
+// scalars are written the way the serializer writes them: numbers as text, booleans as "1"/"0", null as ""
+pub fn from_json(json: &serde_json::Value, mode: JsonMode) -> Result<Value, Error> {
+    match (json, mode) {
+        (serde_json::Value::Null, _) => Ok(Value::Value(String::new())),
+        (serde_json::Value::Bool(b), _) => Ok(Value::Value(if *b { "1" } else { "0" }.to_string())),
+        (serde_json::Value::Number(n), _) => Ok(Value::Value(n.to_string())),
+        (serde_json::Value::String(s), _) => Ok(Value::Value(s.clone())),
+        (serde_json::Value::Object(object), JsonMode::Object) => {
+            let mut section = vec![];
+
+            for (key, value) in object {
+                section.push(KeyValue {
+                    key: key.clone(),
+                    value: from_json(value, mode)?,
+                });
+            }
+
+            Ok(Value::Section(section))
+        }
+        (serde_json::Value::Array(array), JsonMode::Object) => {
+            let mut section = vec![];
+
+            for (index, value) in array.iter().enumerate() {
+                section.push(KeyValue {
+                    key: index.to_string(),
+                    value: from_json(value, mode)?,
+                });
+            }
+
+            Ok(Value::Section(section))
+        }
+        (serde_json::Value::Array(array), JsonMode::Lossless) => {
+            let mut section = vec![];
+
+            for pair in array {
+                match pair.as_array().map(Vec::as_slice) {
+                    Some([serde_json::Value::String(key), value]) => section.push(KeyValue {
+                        key: key.clone(),
+                        value: from_json(value, mode)?,
+                    }),
+                    _ => {
+                        return Err(Error::InvalidJsonError(format!(
+                            "expected a [key, value] pair, found {}",
+                            pair
+                        )))
+                    }
+                }
+            }
+
+            Ok(Value::Section(section))
+        }
+        (serde_json::Value::Object(_), JsonMode::Lossless) => Err(Error::InvalidJsonError(
+            "expected an array of [key, value] pairs, found an object".to_string(),
+        )),
+    }
+}
@@ -1,5 +1,17 @@
+use std::fmt;
+
 use serde::{de::Visitor, ser::SerializeMap, Deserialize, Serialize};
 
+mod diff;
+mod merge;
+mod typed;
+mod visit;
+
+pub use diff::{diff, Change, DiffPath, Patch, PathSegment};
+pub use merge::{merge, merge_with_report, MergeStrategy};
+pub use typed::{TypedKeyValue, TypedValue, ValueType};
+pub use visit::{depth_first, walk, walk_mut, DepthFirst, Visit, VisitMut};
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct KeyValueFile {
     pub imports: Vec<String>,
@@ -27,6 +39,48 @@ pub enum Value {
     Section(Vec<KeyValue>),
 }
 
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Value(value) => Some(value),
+            Value::Section(_) => None,
+        }
+    }
+
+    pub fn as_section(&self) -> Option<&[KeyValue]> {
+        match self {
+            Value::Value(_) => None,
+            Value::Section(section) => Some(section),
+        }
+    }
+
+    pub fn is_section(&self) -> bool {
+        matches!(self, Value::Section(_))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_section()?
+            .iter()
+            .find(|kv| kv.key == key)
+            .map(|kv| &kv.value)
+    }
+
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.as_section()
+            .unwrap_or_default()
+            .iter()
+            .filter(move |kv| kv.key == key)
+            .map(|kv| &kv.value)
+    }
+
+    // follows the first occurrence of every key in a "a/b/c" path
+    pub fn lookup(&self, path: &str) -> Option<&Value> {
+        KeyPath::from(path)
+            .iter()
+            .try_fold(self, |value, key| value.get(key))
+    }
+}
+
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -71,6 +125,34 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(Value::Value(v))
             }
 
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Value::Value(v.to_string()))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Value::Value(v.to_string()))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Value::Value(v.to_string()))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Value::Value(v.to_string()))
+            }
+
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
             where
                 A: serde::de::MapAccess<'de>,
@@ -101,3 +183,63 @@ impl Default for KeyValue {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct KeyPath(pub Vec<String>);
+
+impl KeyPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, key: &str) {
+        self.0.push(key.to_string());
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.0.pop()
+    }
+
+    pub fn join(&self, key: &str) -> KeyPath {
+        let mut path = self.clone();
+        path.push(key);
+        path
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+// segments are separated by '/', since keys like "Hero_Axe.Attack" commonly contain dots
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join("/"))
+    }
+}
+
+impl From<&str> for KeyPath {
+    fn from(value: &str) -> Self {
+        KeyPath(
+            value
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<String>> for KeyPath {
+    fn from(value: Vec<String>) -> Self {
+        KeyPath(value)
+    }
+}
@@ -0,0 +1,498 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    kv::{KeyValue, Value},
+};
+
+// a key together with the index of its occurrence among siblings sharing that key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathSegment {
+    pub key: String,
+    pub occurrence: usize,
+}
+
+// a repeated key gets its occurrence appended: "Game[1]", '/' and '\\' inside keys like
+// "particles/units/axe.vpcf" are escaped with a backslash, both are valid escapes in KV strings
+// so a path survives Patch::to_kv, a key ending in "[n]" itself always gets its occurrence written,
+// the empty key is written as \"\" which no other key escapes to
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            f.write_str(EMPTY_KEY)?;
+        }
+
+        for c in self.key.chars() {
+            if matches!(c, '/' | '\\') {
+                f.write_str("\\")?;
+            }
+
+            write!(f, "{}", c)?;
+        }
+
+        if self.occurrence > 0 || split_occurrence(&self.key).is_some() {
+            write!(f, "[{}]", self.occurrence)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PathSegment {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidPatchError(format!("invalid path segment {}", s));
+
+        let (escaped, occurrence) = split_occurrence(s).unwrap_or((s, 0));
+
+        if escaped == EMPTY_KEY {
+            return Ok(PathSegment {
+                key: String::new(),
+                occurrence,
+            });
+        }
+
+        if escaped.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut key = String::new();
+        let mut chars = escaped.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => key.push(chars.next().ok_or_else(invalid)?),
+                '/' => return Err(invalid()),
+                c => key.push(c),
+            }
+        }
+
+        Ok(PathSegment { key, occurrence })
+    }
+}
+
+const EMPTY_KEY: &str = "\\\"\\\"";
+
+fn split_occurrence(s: &str) -> Option<(&str, usize)> {
+    let (key, occurrence) = s.strip_suffix(']')?.rsplit_once('[')?;
+
+    if occurrence.is_empty() || !occurrence.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((key, occurrence.parse().ok()?))
+}
+
+impl Serialize for PathSegment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PathSegment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DiffPath(pub Vec<PathSegment>);
+
+// rendered as "a/b/c", see PathSegment for the escaping of single keys, the root is "/"
+impl fmt::Display for DiffPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("/");
+        }
+
+        for (index, segment) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str("/")?;
+            }
+
+            write!(f, "{}", segment)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for DiffPath {
+    type Err = Error;
+
+    // every segment has to be there, so "", "a//b" and "/a" are errors instead of other paths
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "/" {
+            return Ok(DiffPath::default());
+        }
+
+        let mut segments = vec![];
+        let mut part = String::new();
+        let mut escaped = false;
+
+        for c in s.chars() {
+            match c {
+                '/' if !escaped => segments.push(std::mem::take(&mut part).parse()?),
+                c => {
+                    escaped = !escaped && c == '\\';
+                    part.push(c);
+                }
+            }
+        }
+
+        segments.push(part.parse()?);
+
+        Ok(DiffPath(segments))
+    }
+}
+
+impl Serialize for DiffPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DiffPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum Change {
+    // `after` is the sibling the entry follows in the new section, None when it comes first
+    #[serde(rename = "add")]
+    Added {
+        path: DiffPath,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<PathSegment>,
+        value: Value,
+    },
+    #[serde(rename = "remove")]
+    Removed { path: DiffPath, value: Value },
+    #[serde(rename = "change")]
+    Changed {
+        path: DiffPath,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &DiffPath {
+        match self {
+            Change::Added { path, .. } => path,
+            Change::Removed { path, .. } => path,
+            Change::Changed { path, .. } => path,
+        }
+    }
+
+    fn to_kv(&self) -> Vec<KeyValue> {
+        let field = |key: &str, value: &Value| KeyValue {
+            key: key.to_string(),
+            value: value.clone(),
+        };
+
+        let (op, mut fields) = match self {
+            Change::Added { after, value, .. } => {
+                let mut fields = vec![field("value", value)];
+
+                if let Some(after) = after {
+                    fields.insert(0, field("after", &Value::Value(after.to_string())));
+                }
+
+                ("add", fields)
+            }
+            Change::Removed { value, .. } => ("remove", vec![field("value", value)]),
+            Change::Changed { old, new, .. } => {
+                ("change", vec![field("old", old), field("new", new)])
+            }
+        };
+
+        let mut kvs = vec![
+            field("op", &Value::Value(op.to_string())),
+            field("path", &Value::Value(self.path().to_string())),
+        ];
+        kvs.append(&mut fields);
+
+        kvs
+    }
+
+    fn from_kv(kvs: &[KeyValue]) -> Result<Change, Error> {
+        let field = |key: &str| {
+            kvs.iter()
+                .find(|kv| kv.key == key)
+                .map(|kv| kv.value.clone())
+                .ok_or_else(|| Error::InvalidPatchError(format!("missing field {}", key)))
+        };
+
+        let text = |key: &str| match field(key)? {
+            Value::Value(v) => Ok(v),
+            Value::Section(_) => Err(Error::InvalidPatchError(format!(
+                "field {} must be a value",
+                key
+            ))),
+        };
+
+        let path = text("path")?.parse()?;
+
+        match text("op")?.as_str() {
+            "add" => Ok(Change::Added {
+                path,
+                after: match kvs.iter().any(|kv| kv.key == "after") {
+                    true => Some(text("after")?.parse()?),
+                    false => None,
+                },
+                value: field("value")?,
+            }),
+            "remove" => Ok(Change::Removed {
+                path,
+                value: field("value")?,
+            }),
+            "change" => Ok(Change::Changed {
+                path,
+                old: field("old")?,
+                new: field("new")?,
+            }),
+            op => Err(Error::InvalidPatchError(format!("unknown op {}", op))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch {
+    pub changes: Vec<Change>,
+}
+
+impl Patch {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn to_kv(&self) -> Vec<KeyValue> {
+        self.changes
+            .iter()
+            .enumerate()
+            .map(|(index, change)| KeyValue {
+                key: index.to_string(),
+                value: Value::Section(change.to_kv()),
+            })
+            .collect()
+    }
+
+    pub fn from_kv(kvs: &[KeyValue]) -> Result<Patch, Error> {
+        let mut changes = vec![];
+
+        for kv in kvs {
+            match &kv.value {
+                Value::Section(section) => changes.push(Change::from_kv(section)?),
+                Value::Value(_) => {
+                    return Err(Error::InvalidPatchError(format!(
+                        "change {} must be a section",
+                        kv.key
+                    )))
+                }
+            }
+        }
+
+        Ok(Patch { changes })
+    }
+
+    pub fn apply(&self, target: &mut Value) -> Result<(), Error> {
+        for change in &self.changes {
+            apply_change(target, change)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn diff(old: &Value, new: &Value) -> Patch {
+    let mut patch = Patch::default();
+
+    diff_value(old, new, &mut DiffPath::default(), &mut patch.changes);
+
+    patch
+}
+
+fn diff_value(old: &Value, new: &Value, path: &mut DiffPath, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Section(old), Value::Section(new)) => diff_section(old, new, path, changes),
+        _ if old != new => changes.push(Change::Changed {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => (),
+    }
+}
+
+// the n-th occurrence of a key in the old section is compared against the n-th occurrence in the new one
+fn diff_section(
+    old: &[KeyValue],
+    new: &[KeyValue],
+    path: &mut DiffPath,
+    changes: &mut Vec<Change>,
+) {
+    let mut new_positions: HashMap<&str, Vec<usize>> = HashMap::new();
+
+    for (index, kv) in new.iter().enumerate() {
+        new_positions.entry(&kv.key).or_default().push(index);
+    }
+
+    let mut old_occurrences: HashMap<&str, usize> = HashMap::new();
+    let mut removed = vec![];
+
+    for kv in old {
+        let occurrence = old_occurrences.entry(&kv.key).or_default();
+
+        path.0.push(PathSegment {
+            key: kv.key.clone(),
+            occurrence: *occurrence,
+        });
+
+        match new_positions
+            .get(kv.key.as_str())
+            .and_then(|p| p.get(*occurrence))
+        {
+            Some(&index) => diff_value(&kv.value, &new[index].value, path, changes),
+            None => removed.push(Change::Removed {
+                path: path.clone(),
+                value: kv.value.clone(),
+            }),
+        }
+
+        path.0.pop();
+        *occurrence += 1;
+    }
+
+    // removing the last occurrences first keeps the remaining occurrence indices valid
+    changes.extend(removed.into_iter().rev());
+
+    let mut new_occurrences: HashMap<&str, usize> = HashMap::new();
+    let mut after = None;
+
+    for kv in new {
+        let occurrence = new_occurrences.entry(&kv.key).or_default();
+
+        let segment = PathSegment {
+            key: kv.key.clone(),
+            occurrence: *occurrence,
+        };
+
+        if *occurrence >= old_occurrences.get(kv.key.as_str()).copied().unwrap_or(0) {
+            path.0.push(segment.clone());
+
+            changes.push(Change::Added {
+                path: path.clone(),
+                after: after.clone(),
+                value: kv.value.clone(),
+            });
+
+            path.0.pop();
+        }
+
+        after = Some(segment);
+        *occurrence += 1;
+    }
+}
+
+fn apply_change(target: &mut Value, change: &Change) -> Result<(), Error> {
+    let path = change.path();
+
+    let Some((last, parent)) = path.0.split_last() else {
+        return match change {
+            Change::Changed { new, .. } => {
+                *target = new.clone();
+                Ok(())
+            }
+            _ => Err(Error::InvalidPatchError(
+                "only a change can target the root".to_string(),
+            )),
+        };
+    };
+
+    let not_found = || Error::PathNotFoundError(path.to_string());
+
+    let mut section = match target {
+        Value::Section(section) => section,
+        Value::Value(_) => return Err(not_found()),
+    };
+
+    for segment in parent {
+        let index = position(section, segment).ok_or_else(not_found)?;
+
+        section = match &mut section[index].value {
+            Value::Section(section) => section,
+            Value::Value(_) => return Err(not_found()),
+        };
+    }
+
+    match change {
+        // additions are applied in the order of the new section, so the sibling is already in place,
+        // an added occurrence still has to follow the earlier occurrences of its key
+        Change::Added { after, value, .. } => {
+            let mut index = match after {
+                Some(after) => position(section, after).ok_or_else(not_found)? + 1,
+                None => 0,
+            };
+
+            if let Some(occurrence) = last.occurrence.checked_sub(1) {
+                let previous = PathSegment {
+                    key: last.key.clone(),
+                    occurrence,
+                };
+
+                index = index.max(position(section, &previous).ok_or_else(not_found)? + 1);
+            }
+
+            section.insert(
+                index,
+                KeyValue {
+                    key: last.key.clone(),
+                    value: value.clone(),
+                },
+            );
+        }
+        Change::Removed { .. } => {
+            let index = position(section, last).ok_or_else(not_found)?;
+            section.remove(index);
+        }
+        Change::Changed { new, .. } => {
+            let index = position(section, last).ok_or_else(not_found)?;
+            section[index].value = new.clone();
+        }
+    }
+
+    Ok(())
+}
+
+fn position(section: &[KeyValue], segment: &PathSegment) -> Option<usize> {
+    section
+        .iter()
+        .enumerate()
+        .filter(|(_, kv)| kv.key == segment.key)
+        .nth(segment.occurrence)
+        .map(|(index, _)| index)
+}
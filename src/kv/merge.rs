@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::kv::{KeyPath, KeyValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    // sections present in both trees are merged key by key, overlay values win
+    #[default]
+    Recursive,
+    // a key present in both trees takes the overlay value as a whole
+    Replace,
+    // overlay kvs are appended after the base ones
+    Append,
+    // sections are merged key by key, base values win
+    KeepBase,
+}
+
+pub fn merge(
+    base: Vec<KeyValue>,
+    overlay: Vec<KeyValue>,
+    strategy: MergeStrategy,
+) -> Vec<KeyValue> {
+    merge_section(base, overlay, strategy, &mut KeyPath::new(), &mut None)
+}
+
+pub fn merge_with_report(
+    base: Vec<KeyValue>,
+    overlay: Vec<KeyValue>,
+    strategy: MergeStrategy,
+) -> (Vec<KeyValue>, Vec<KeyPath>) {
+    let mut report = Some(vec![]);
+
+    let merged = merge_section(base, overlay, strategy, &mut KeyPath::new(), &mut report);
+
+    (merged, report.unwrap_or_default())
+}
+
+// the n-th occurrence of a key in the overlay is matched against the n-th occurrence of the same key in the base
+fn merge_section(
+    mut base: Vec<KeyValue>,
+    overlay: Vec<KeyValue>,
+    strategy: MergeStrategy,
+    path: &mut KeyPath,
+    report: &mut Option<Vec<KeyPath>>,
+) -> Vec<KeyValue> {
+    if strategy == MergeStrategy::Append {
+        base.extend(overlay);
+        return base;
+    }
+
+    let mut positions: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (index, kv) in base.iter().enumerate() {
+        positions.entry(kv.key.clone()).or_default().push(index);
+    }
+
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    for kv in overlay {
+        let occurrence = occurrences.entry(kv.key.clone()).or_default();
+        let position = positions
+            .get(&kv.key)
+            .and_then(|p| p.get(*occurrence))
+            .copied();
+
+        *occurrence += 1;
+
+        match position {
+            Some(index) => {
+                path.push(&kv.key);
+                merge_value(&mut base[index], kv.value, strategy, path, report);
+                path.pop();
+            }
+            None => base.push(kv),
+        }
+    }
+
+    base
+}
+
+fn merge_value(
+    target: &mut KeyValue,
+    value: Value,
+    strategy: MergeStrategy,
+    path: &mut KeyPath,
+    report: &mut Option<Vec<KeyPath>>,
+) {
+    match (&mut target.value, value) {
+        (Value::Section(base), Value::Section(overlay))
+            if strategy == MergeStrategy::Recursive || strategy == MergeStrategy::KeepBase =>
+        {
+            let base = std::mem::take(base);
+
+            target.value = Value::Section(merge_section(base, overlay, strategy, path, report));
+        }
+        _ if strategy == MergeStrategy::KeepBase => {}
+        (_, value) => {
+            if target.value != value {
+                if let Some(report) = report {
+                    report.push(path.clone());
+                }
+
+                target.value = value;
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{ser::SerializeMap, Serialize};
+
+use crate::{
+    error::Error,
+    kv::{KeyValue, Value},
+};
+
+// the value types of Valve's KeyValues class
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    String,
+    Int,
+    Float,
+    Pointer,
+    WString,
+    Color,
+    Uint64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedKeyValue {
+    pub key: String,
+    pub value: TypedValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    String(String),
+    Int(i32),
+    Float(f32),
+    Pointer(u32),
+    WString(String),
+    Color([u8; 4]),
+    Uint64(u64),
+    Section(Vec<TypedKeyValue>),
+}
+
+impl TypedValue {
+    pub fn value_type(&self) -> Option<ValueType> {
+        match self {
+            TypedValue::String(_) => Some(ValueType::String),
+            TypedValue::Int(_) => Some(ValueType::Int),
+            TypedValue::Float(_) => Some(ValueType::Float),
+            TypedValue::Pointer(_) => Some(ValueType::Pointer),
+            TypedValue::WString(_) => Some(ValueType::WString),
+            TypedValue::Color(_) => Some(ValueType::Color),
+            TypedValue::Uint64(_) => Some(ValueType::Uint64),
+            TypedValue::Section(_) => None,
+        }
+    }
+
+    pub fn parse(text: &str, value_type: ValueType) -> Result<TypedValue, Error> {
+        let invalid = || Error::ParseTypedValueError(format!("{:?} from {:?}", value_type, text));
+
+        Ok(match value_type {
+            ValueType::String => TypedValue::String(text.to_string()),
+            ValueType::WString => TypedValue::WString(text.to_string()),
+            ValueType::Int => TypedValue::Int(text.parse().map_err(|_| invalid())?),
+            ValueType::Float => TypedValue::Float(text.parse().map_err(|_| invalid())?),
+            ValueType::Pointer => TypedValue::Pointer(text.parse().map_err(|_| invalid())?),
+            ValueType::Uint64 => TypedValue::Uint64(text.parse().map_err(|_| invalid())?),
+            ValueType::Color => {
+                let mut color = [0; 4];
+                let mut parts = text.split_ascii_whitespace();
+
+                for channel in color.iter_mut() {
+                    *channel = parts
+                        .next()
+                        .and_then(|p| p.parse().ok())
+                        .ok_or_else(invalid)?;
+                }
+
+                if parts.next().is_some() {
+                    return Err(invalid());
+                }
+
+                TypedValue::Color(color)
+            }
+        })
+    }
+
+    // only picks a numeric type when it prints back to exactly the same text, colors are never inferred
+    pub fn infer(text: &str) -> TypedValue {
+        // "inf" and "nan" print back unchanged but are plain strings in practice
+        let lossless = |value: TypedValue| match value {
+            TypedValue::Float(v) if !v.is_finite() => None,
+            _ if value.to_text().as_deref() == Some(text) => Some(value),
+            _ => None,
+        };
+
+        [ValueType::Int, ValueType::Uint64, ValueType::Float]
+            .into_iter()
+            .filter_map(|value_type| TypedValue::parse(text, value_type).ok())
+            .find_map(lossless)
+            .unwrap_or_else(|| TypedValue::String(text.to_string()))
+    }
+
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            TypedValue::String(v) | TypedValue::WString(v) => Some(v.clone()),
+            TypedValue::Int(v) => Some(v.to_string()),
+            TypedValue::Float(v) => Some(v.to_string()),
+            TypedValue::Pointer(v) => Some(v.to_string()),
+            TypedValue::Uint64(v) => Some(v.to_string()),
+            TypedValue::Color([r, g, b, a]) => Some(format!("{} {} {} {}", r, g, b, a)),
+            TypedValue::Section(_) => None,
+        }
+    }
+}
+
+impl From<&Value> for TypedValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Value(text) => TypedValue::infer(text),
+            Value::Section(section) => {
+                TypedValue::Section(section.iter().map(TypedKeyValue::from).collect())
+            }
+        }
+    }
+}
+
+impl From<&TypedValue> for Value {
+    fn from(value: &TypedValue) -> Self {
+        match value {
+            TypedValue::Section(section) => {
+                Value::Section(section.iter().map(KeyValue::from).collect())
+            }
+            value => Value::Value(value.to_text().unwrap_or_default()),
+        }
+    }
+}
+
+impl From<&KeyValue> for TypedKeyValue {
+    fn from(kv: &KeyValue) -> Self {
+        TypedKeyValue {
+            key: kv.key.clone(),
+            value: TypedValue::from(&kv.value),
+        }
+    }
+}
+
+impl From<&TypedKeyValue> for KeyValue {
+    fn from(kv: &TypedKeyValue) -> Self {
+        KeyValue {
+            key: kv.key.clone(),
+            value: Value::from(&kv.value),
+        }
+    }
+}
+
+impl Serialize for TypedValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            TypedValue::String(v) | TypedValue::WString(v) => serializer.serialize_str(v),
+            TypedValue::Int(v) => serializer.serialize_i32(*v),
+            // widened through its text so 1.7 doesn't print as 1.7000000476837158
+            TypedValue::Float(v) => {
+                serializer.serialize_f64(v.to_string().parse().unwrap_or(f64::from(*v)))
+            }
+            TypedValue::Pointer(v) => serializer.serialize_u32(*v),
+            TypedValue::Uint64(v) => serializer.serialize_u64(*v),
+            TypedValue::Color(_) => serializer.serialize_str(&self.to_text().unwrap_or_default()),
+            TypedValue::Section(section) => {
+                let mut state = serializer.serialize_map(Some(section.len()))?;
+
+                for kv in section {
+                    state.serialize_entry(&kv.key, &kv.value)?;
+                }
+
+                state.end()
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::slice;
+
+use crate::kv::{KeyPath, KeyValue, Value};
+
+// paths passed to the callbacks end with the key of the visited kv
+pub trait Visit {
+    fn visit_value(&mut self, _path: &KeyPath, _kv: &KeyValue) {}
+
+    // returning false skips the children of the section
+    fn enter_section(&mut self, _path: &KeyPath, _kv: &KeyValue) -> bool {
+        true
+    }
+
+    fn leave_section(&mut self, _path: &KeyPath, _kv: &KeyValue) {}
+}
+
+pub trait VisitMut {
+    fn visit_value_mut(&mut self, _path: &KeyPath, _kv: &mut KeyValue) {}
+
+    // returning false skips the children of the section, renaming the key changes the children paths
+    fn enter_section_mut(&mut self, _path: &KeyPath, _kv: &mut KeyValue) -> bool {
+        true
+    }
+
+    fn leave_section_mut(&mut self, _path: &KeyPath, _kv: &mut KeyValue) {}
+}
+
+pub fn walk<V>(visitor: &mut V, kvs: &[KeyValue])
+where
+    V: Visit + ?Sized,
+{
+    walk_impl(visitor, kvs, &mut KeyPath::new());
+}
+
+fn walk_impl<V>(visitor: &mut V, kvs: &[KeyValue], path: &mut KeyPath)
+where
+    V: Visit + ?Sized,
+{
+    for kv in kvs {
+        path.push(&kv.key);
+
+        match &kv.value {
+            Value::Value(_) => visitor.visit_value(path, kv),
+            Value::Section(section) => {
+                if visitor.enter_section(path, kv) {
+                    walk_impl(visitor, section, path);
+                }
+
+                visitor.leave_section(path, kv);
+            }
+        }
+
+        path.pop();
+    }
+}
+
+pub fn walk_mut<V>(visitor: &mut V, kvs: &mut [KeyValue])
+where
+    V: VisitMut + ?Sized,
+{
+    walk_mut_impl(visitor, kvs, &mut KeyPath::new());
+}
+
+fn walk_mut_impl<V>(visitor: &mut V, kvs: &mut [KeyValue], path: &mut KeyPath)
+where
+    V: VisitMut + ?Sized,
+{
+    for kv in kvs {
+        path.push(&kv.key);
+
+        if let Value::Value(_) = kv.value {
+            visitor.visit_value_mut(path, kv);
+        } else {
+            let descend = visitor.enter_section_mut(path, kv);
+
+            path.pop();
+            path.push(&kv.key);
+
+            if descend {
+                if let Value::Section(section) = &mut kv.value {
+                    walk_mut_impl(visitor, section, path);
+                }
+            }
+
+            visitor.leave_section_mut(path, kv);
+        }
+
+        path.pop();
+    }
+}
+
+pub struct DepthFirst<'a> {
+    stack: Vec<slice::Iter<'a, KeyValue>>,
+    path: KeyPath,
+}
+
+pub fn depth_first(kvs: &[KeyValue]) -> DepthFirst<'_> {
+    DepthFirst {
+        stack: vec![kvs.iter()],
+        path: KeyPath::new(),
+    }
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = (KeyPath, &'a KeyValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let iter = self.stack.last_mut()?;
+
+            let Some(kv) = iter.next() else {
+                self.stack.pop();
+                self.path.pop();
+                continue;
+            };
+
+            let path = self.path.join(&kv.key);
+
+            if let Value::Section(section) = &kv.value {
+                self.stack.push(section.iter());
+                self.path.push(&kv.key);
+            }
+
+            return Some((path, kv));
+        }
+    }
+}
@@ -1,5 +1,12 @@
+pub mod canonical;
 pub mod deserializer;
+pub mod document;
 pub mod error;
+pub mod formatter;
+pub mod json;
 pub mod kv;
+pub mod lint;
 pub mod parser;
+pub mod schema;
 pub mod serializer;
+pub mod with;
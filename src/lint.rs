@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
+
+use crate::{
+    error::Error,
+    kv::KeyPath,
+    parser::{parse_input, parse_input_spanned, Span, SpannedFile},
+};
+
+mod rules;
+
+pub use rules::{
+    DuplicateKeys, EmptySection, KeyCaseConflict, SuspiciousNumber, TrailingWhitespace,
+    UnreachableBase,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    // byte range of the original input
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub message: String,
+    pub path: KeyPath,
+    pub span: Span,
+    pub fix: Option<Vec<Edit>>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {} [{}]",
+            self.span.start, self.path, self.message, self.rule
+        )
+    }
+}
+
+pub struct LintContext<'a> {
+    pub source: &'a str,
+    pub file: &'a SpannedFile,
+    // where the source was read from, rules looking at other files are skipped without it
+    pub path: Option<&'a Path>,
+}
+
+pub trait LintRule {
+    // used in configs and reported with every diagnostic
+    fn name(&self) -> &'static str;
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>);
+}
+
+// rules are enabled unless the config says otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LintConfig {
+    pub rules: HashMap<String, bool>,
+}
+
+impl LintConfig {
+    pub fn enable(&mut self, rule: &str) {
+        self.rules.insert(rule.to_string(), true);
+    }
+
+    pub fn disable(&mut self, rule: &str) {
+        self.rules.insert(rule.to_string(), false);
+    }
+
+    pub fn is_enabled(&self, rule: &str) -> bool {
+        self.rules.get(rule).copied().unwrap_or(true)
+    }
+}
+
+// a KV file of rule names mapped to "1" or "0"
+impl FromStr for LintConfig {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let mut config = LintConfig::default();
+
+        for kv in parse_input(input)?.kvs {
+            match kv.value.as_str() {
+                Some("1") => config.enable(&kv.key),
+                Some("0") => config.disable(&kv.key),
+                _ => {
+                    return Err(Error::InvalidLintConfigError(format!(
+                        "lint rule {} must be set to \"0\" or \"1\"",
+                        kv.key
+                    )))
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    config: LintConfig,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new()
+    }
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Linter::with_config(LintConfig::default())
+    }
+
+    pub fn with_config(config: LintConfig) -> Self {
+        Linter {
+            rules: vec![
+                Box::new(DuplicateKeys),
+                Box::new(KeyCaseConflict),
+                Box::new(EmptySection),
+                Box::new(TrailingWhitespace),
+                Box::new(SuspiciousNumber),
+                Box::new(UnreachableBase),
+            ],
+            config,
+        }
+    }
+
+    pub fn add_rule<R>(&mut self, rule: R)
+    where
+        R: LintRule + 'static,
+    {
+        self.rules.push(Box::new(rule));
+    }
+
+    pub fn config_mut(&mut self) -> &mut LintConfig {
+        &mut self.config
+    }
+
+    pub fn lint_str(&self, input: &str) -> Result<Vec<Diagnostic>, Error> {
+        self.lint(input, None)
+    }
+
+    pub fn lint_file(&self, path: &str) -> Result<Vec<Diagnostic>, Error> {
+        let input = String::from_utf8(fs::read(path).map_err(Error::ReadFileError)?)
+            .map_err(Error::ReadUtf8Error)?;
+
+        self.lint(&input, Some(Path::new(path)))
+    }
+
+    fn lint(&self, source: &str, path: Option<&Path>) -> Result<Vec<Diagnostic>, Error> {
+        let file = parse_input_spanned(source)?;
+
+        let context = LintContext {
+            source,
+            file: &file,
+            path,
+        };
+
+        let mut diagnostics = vec![];
+
+        for rule in &self.rules {
+            if self.config.is_enabled(rule.name()) {
+                rule.check(&context, &mut diagnostics);
+            }
+        }
+
+        diagnostics.sort_by_key(|d| d.span.start.offset);
+
+        Ok(diagnostics)
+    }
+
+    // applies every available fix, the rest of the diagnostics are returned untouched
+    pub fn fix_str(&self, input: &str) -> Result<(String, Vec<Diagnostic>), Error> {
+        let diagnostics = self.lint_str(input)?;
+        Ok(apply_fixes(input, diagnostics))
+    }
+}
+
+// fixes overlapping an earlier one are skipped and their diagnostics kept
+pub fn apply_fixes(input: &str, diagnostics: Vec<Diagnostic>) -> (String, Vec<Diagnostic>) {
+    let mut edits: Vec<Edit> = vec![];
+    let mut remaining = vec![];
+
+    for diagnostic in diagnostics {
+        let fix = match &diagnostic.fix {
+            Some(fix) => fix,
+            None => {
+                remaining.push(diagnostic);
+                continue;
+            }
+        };
+
+        let overlaps = fix.iter().any(|edit| {
+            edits
+                .iter()
+                .any(|other| edit.start < other.end && other.start < edit.end)
+        });
+
+        if overlaps {
+            remaining.push(diagnostic);
+        } else {
+            edits.extend(fix.iter().cloned());
+        }
+    }
+
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+
+    let mut output = input.to_string();
+
+    for edit in edits {
+        output.replace_range(edit.start..edit.end, &edit.replacement);
+    }
+
+    (output, remaining)
+}
@@ -0,0 +1,347 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    kv::KeyPath,
+    lint::{Diagnostic, Edit, LintContext, LintRule},
+    parser::{SpannedKeyValue, SpannedValue},
+};
+
+// the same key twice in one section, deserializing into a struct keeps only one of them
+pub struct DuplicateKeys;
+
+impl LintRule for DuplicateKeys {
+    fn name(&self) -> &'static str {
+        "duplicate-keys"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for_each_section(
+            &context.file.kvs,
+            &mut KeyPath::default(),
+            &mut |kvs, path| {
+                let mut seen: HashMap<&str, usize> = HashMap::new();
+
+                for kv in kvs {
+                    let count = seen.entry(&kv.key).or_default();
+                    *count += 1;
+
+                    if *count == 2 {
+                        diagnostics.push(Diagnostic {
+                            rule: self.name(),
+                            message: format!("duplicate key \"{}\"", kv.key),
+                            path: path.join(&kv.key),
+                            span: kv.key_span,
+                            fix: None,
+                        });
+                    }
+                }
+            },
+        );
+    }
+}
+
+// keys differing only in case, lookups in the engine are case insensitive
+pub struct KeyCaseConflict;
+
+impl LintRule for KeyCaseConflict {
+    fn name(&self) -> &'static str {
+        "key-case"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for_each_section(
+            &context.file.kvs,
+            &mut KeyPath::default(),
+            &mut |kvs, path| {
+                // the first spelling and the keys its section holds once the fixes are applied
+                let mut first: HashMap<String, (&SpannedKeyValue, HashSet<String>)> =
+                    HashMap::new();
+
+                for kv in kvs {
+                    let (spelling, merged) = first
+                        .entry(kv.key.to_lowercase())
+                        .or_insert_with(|| (kv, child_keys(kv)));
+
+                    if spelling.key != kv.key {
+                        // renaming the key would only turn it into a duplicate of the first
+                        // spelling, so a section is merged into the first one instead when
+                        // none of its keys are already there
+                        let fix = match (&spelling.value, &kv.value) {
+                            (SpannedValue::Section(_), SpannedValue::Section(section))
+                                if child_keys(kv).is_disjoint(merged) =>
+                            {
+                                merged.extend(child_keys(kv));
+                                Some(merge_section(context.source, spelling, kv, section))
+                            }
+                            _ => None,
+                        };
+
+                        diagnostics.push(Diagnostic {
+                            rule: self.name(),
+                            message: format!(
+                                "key \"{}\" differs from \"{}\" only in case",
+                                kv.key, spelling.key
+                            ),
+                            path: path.join(&kv.key),
+                            span: kv.key_span,
+                            fix,
+                        });
+                    }
+                }
+            },
+        );
+    }
+}
+
+// lowercased keys of a section, nothing for a value
+fn child_keys(kv: &SpannedKeyValue) -> HashSet<String> {
+    match &kv.value {
+        SpannedValue::Section(section) => section.iter().map(|kv| kv.key.to_lowercase()).collect(),
+        SpannedValue::Value(_) => HashSet::new(),
+    }
+}
+
+// moves the pairs of `kv` to the end of the `into` section and removes `kv`
+fn merge_section(
+    source: &str,
+    into: &SpannedKeyValue,
+    kv: &SpannedKeyValue,
+    section: &[SpannedKeyValue],
+) -> Vec<Edit> {
+    let (mut start, end) = whole_lines(source, kv.span.start.offset, kv.span.end.offset);
+    if start == kv.span.start.offset {
+        start = source[..start].trim_end_matches([' ', '\t']).len();
+    }
+
+    let mut edits = vec![Edit {
+        start,
+        end,
+        replacement: String::new(),
+    }];
+
+    if let (Some(first), Some(last)) = (section.first(), section.last()) {
+        let (start, end) = whole_lines(source, first.span.start.offset, last.span.end.offset);
+        let pairs = &source[start..end];
+
+        // right before the closing brace, on a line of its own when it has one
+        let brace = into.value_span.end.offset - 1;
+        let line_start = source[..brace].rfind('\n').map_or(0, |i| i + 1);
+
+        let (offset, replacement) =
+            if pairs.ends_with('\n') && source[line_start..brace].trim().is_empty() {
+                (line_start, pairs.to_string())
+            } else if source[..brace].ends_with(char::is_whitespace) {
+                (brace, format!("{} ", pairs.trim()))
+            } else {
+                (brace, format!(" {} ", pairs.trim()))
+            };
+
+        edits.push(Edit {
+            start: offset,
+            end: offset,
+            replacement,
+        });
+    }
+
+    edits
+}
+
+// only reported, an empty section can be meant to clear the same section of a #base file
+pub struct EmptySection;
+
+impl LintRule for EmptySection {
+    fn name(&self) -> &'static str {
+        "empty-section"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for_each_kv(
+            &context.file.kvs,
+            &mut KeyPath::default(),
+            &mut |kv, path| {
+                if matches!(&kv.value, SpannedValue::Section(section) if section.is_empty()) {
+                    diagnostics.push(Diagnostic {
+                        rule: self.name(),
+                        message: format!("section \"{}\" is empty", kv.key),
+                        path: path.clone(),
+                        span: kv.span,
+                        fix: None,
+                    });
+                }
+            },
+        );
+    }
+}
+
+pub struct TrailingWhitespace;
+
+impl LintRule for TrailingWhitespace {
+    fn name(&self) -> &'static str {
+        "trailing-whitespace"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for_each_kv(
+            &context.file.kvs,
+            &mut KeyPath::default(),
+            &mut |kv, path| {
+                if let SpannedValue::Value(v) = &kv.value {
+                    let trimmed = v.trim_end();
+
+                    if trimmed.len() != v.len() {
+                        // inside the quotes
+                        let start = kv.value_span.start.offset + 1;
+
+                        diagnostics.push(Diagnostic {
+                            rule: self.name(),
+                            message: format!("value of \"{}\" ends with whitespace", kv.key),
+                            path: path.clone(),
+                            span: kv.value_span,
+                            fix: Some(vec![Edit {
+                                start: start + trimmed.len(),
+                                end: start + v.len(),
+                                replacement: String::new(),
+                            }]),
+                        });
+                    }
+                }
+            },
+        );
+    }
+}
+
+// "1,5" reads as a string everywhere a number is expected
+pub struct SuspiciousNumber;
+
+impl LintRule for SuspiciousNumber {
+    fn name(&self) -> &'static str {
+        "suspicious-number"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for_each_kv(
+            &context.file.kvs,
+            &mut KeyPath::default(),
+            &mut |kv, path| {
+                let v = match &kv.value {
+                    SpannedValue::Value(v) => v,
+                    SpannedValue::Section(_) => return,
+                };
+
+                let groups: Vec<&str> = v.trim_start_matches(['+', '-']).split(',').collect();
+
+                let numeric = groups.len() > 1
+                    && groups
+                        .iter()
+                        .all(|g| !g.is_empty() && g.chars().all(|c| c.is_ascii_digit()));
+
+                if !numeric {
+                    return;
+                }
+
+                // "1,000" might as well be a thousands separator
+                let decimal_comma = groups.len() == 2 && groups[1].len() != 3;
+
+                let fix = decimal_comma.then(|| {
+                    let start = kv.value_span.start.offset + 1;
+                    let comma = v.find(',').unwrap_or(0);
+
+                    vec![Edit {
+                        start: start + comma,
+                        end: start + comma + 1,
+                        replacement: ".".to_string(),
+                    }]
+                });
+
+                diagnostics.push(Diagnostic {
+                    rule: self.name(),
+                    message: format!(
+                        "value \"{}\" of \"{}\" looks like a number with a comma",
+                        v, kv.key
+                    ),
+                    path: path.clone(),
+                    span: kv.value_span,
+                    fix,
+                });
+            },
+        );
+    }
+}
+
+// #base paths are resolved next to the file, like parser::parse_file does
+pub struct UnreachableBase;
+
+impl LintRule for UnreachableBase {
+    fn name(&self) -> &'static str {
+        "unreachable-base"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        let dir = match context.path.and_then(|path| path.parent()) {
+            Some(dir) => dir,
+            None => return,
+        };
+
+        for (import, span) in &context.file.imports {
+            if !dir.join(import).is_file() {
+                diagnostics.push(Diagnostic {
+                    rule: self.name(),
+                    message: format!("base file \"{}\" does not exist", import),
+                    path: KeyPath::default(),
+                    span: *span,
+                    fix: None,
+                });
+            }
+        }
+    }
+}
+
+// calls `f` for the root and every nested section with the path of the section
+fn for_each_section<'a, F>(kvs: &'a [SpannedKeyValue], path: &mut KeyPath, f: &mut F)
+where
+    F: FnMut(&'a [SpannedKeyValue], &KeyPath),
+{
+    f(kvs, path);
+
+    for kv in kvs {
+        if let SpannedValue::Section(section) = &kv.value {
+            path.push(&kv.key);
+            for_each_section(section, path, f);
+            path.pop();
+        }
+    }
+}
+
+// calls `f` for every pair with the path leading to it, the pair's key included
+fn for_each_kv<F>(kvs: &[SpannedKeyValue], path: &mut KeyPath, f: &mut F)
+where
+    F: FnMut(&SpannedKeyValue, &KeyPath),
+{
+    for kv in kvs {
+        path.push(&kv.key);
+        f(kv, path);
+
+        if let SpannedValue::Section(section) = &kv.value {
+            for_each_kv(section, path, f);
+        }
+
+        path.pop();
+    }
+}
+
+// grows the range to full lines when nothing else shares them
+fn whole_lines(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |i| end + i + 1);
+
+    let before = &source[line_start..start];
+    let after = &source[end..line_end];
+
+    if before.trim().is_empty() && after.trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, fs, path::Path};
+use std::{collections::VecDeque, fmt, fs, path::Path};
 
 use pest::{
     iterators::{Pair, Pairs},
@@ -8,17 +8,40 @@ use pest_derive::Parser;
 
 use crate::{
     error::Error,
-    kv::{KeyValue, KeyValueFile, Value},
+    kv::{KeyPath, KeyValue, KeyValueFile, Value},
 };
 
 pub fn parse_file(path: &str) -> Result<Vec<KeyValue>, Error> {
+    let file = parse_file_impl(path)?;
+
+    let mut res = file.kvs;
+    res.append(&mut parse_bases(path, file.imports)?);
+
+    Ok(res)
+}
+
+// like parse_file, but the kvs of the file itself keep their positions,
+// the ones of its #base files are returned separately since they come from other inputs
+pub fn parse_file_spanned(path: &str) -> Result<(SpannedFile, Vec<KeyValue>), Error> {
+    let file = parse_input_spanned(&read_file(path)?)?;
+
+    let imports = file.imports.iter().map(|(path, _)| path.clone()).collect();
+    let bases = parse_bases(path, imports)?;
+
+    Ok((file, bases))
+}
+
+// the kvs of every #base file, in breadth first order, paths are relative to the root file
+fn parse_bases(path: &str, imports: Vec<String>) -> Result<Vec<KeyValue>, Error> {
     let base_path = Path::new(path)
         .parent()
         .and_then(|s| s.to_str())
         .unwrap_or("");
 
-    let mut paths: VecDeque<String> = Default::default();
-    paths.push_back(path.to_string());
+    let mut paths: VecDeque<String> = imports
+        .into_iter()
+        .map(|import| format!("{}/{}", base_path, import))
+        .collect();
 
     let mut res: Vec<KeyValue> = vec![];
 
@@ -37,10 +60,11 @@ pub fn parse_file(path: &str) -> Result<Vec<KeyValue>, Error> {
 }
 
 fn parse_file_impl(path: &str) -> Result<KeyValueFile, Error> {
-    let file = String::from_utf8(fs::read(path).map_err(Error::ReadFileError)?)
-        .map_err(Error::ReadUtf8Error)?;
+    parse_input(&read_file(path)?)
+}
 
-    parse_input(&file)
+fn read_file(path: &str) -> Result<String, Error> {
+    String::from_utf8(fs::read(path).map_err(Error::ReadFileError)?).map_err(Error::ReadUtf8Error)
 }
 
 #[derive(Parser)]
@@ -48,57 +72,350 @@ fn parse_file_impl(path: &str) -> Result<KeyValueFile, Error> {
 pub struct KeyValueParser;
 
 pub fn parse_input(input: &str) -> Result<KeyValueFile, Error> {
+    let (imports, kvs) = walk_input(input, &mut KvBuilder)?;
+
+    Ok(KeyValueFile {
+        imports: imports.into_iter().map(parse_import).collect(),
+        kvs,
+    })
+}
+
+pub(crate) fn parse_import(input: Pair<Rule>) -> String {
+    input
+        .as_str()
+        .trim_start_matches("#base")
+        .trim_matches(|p: char| p == '"' || p.is_ascii_whitespace())
+        .to_string()
+}
+
+// the text of a key or value pair without its quotes, only the outer ones so a value ending
+// in an escaped quote like "\"a\"" keeps it
+pub(crate) fn unquote<'i>(pair: &Pair<'i, Rule>) -> &'i str {
+    let text = pair.as_str();
+    text.strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+// builds one kind of tree out of the parsed pairs, children are built before their section
+pub(crate) trait PairVisitor {
+    type Output;
+
+    fn visit_value(
+        &mut self,
+        pair: &Pair<Rule>,
+        key: &Pair<Rule>,
+        value: &Pair<Rule>,
+    ) -> Self::Output;
+
+    fn visit_section(
+        &mut self,
+        pair: &Pair<Rule>,
+        key: &Pair<Rule>,
+        section: &Pair<Rule>,
+        children: Vec<Self::Output>,
+    ) -> Self::Output;
+}
+
+// the import pairs and the visited top-level kvs of an input
+pub(crate) type Walked<'i, T> = (Vec<Pair<'i, Rule>>, Vec<T>);
+
+pub(crate) fn walk_input<'i, V>(
+    input: &'i str,
+    visitor: &mut V,
+) -> Result<Walked<'i, V::Output>, Error>
+where
+    V: PairVisitor,
+{
     let pairs = KeyValueParser::parse(Rule::file, input)
         .map_err(|e| Error::ParseKeyValueError(Box::new(e)))?;
 
-    let mut kvf = KeyValueFile::default();
+    let mut imports = vec![];
+    let mut kvs = vec![];
 
     for pair_outer in pairs {
         if let Rule::file = pair_outer.as_rule() {
             for pair in pair_outer.into_inner() {
                 match pair.as_rule() {
-                    Rule::import => kvf.imports.push(parse_import(pair)),
-                    Rule::keyvalue => kvf.kvs.push(parse_single_kv(pair)),
+                    Rule::import => imports.push(pair),
+                    Rule::keyvalue => kvs.push(walk_pair(pair, visitor)),
                     _ => (),
                 }
             }
         }
     }
 
-    Ok(kvf)
+    Ok((imports, kvs))
 }
 
-fn parse_import(input: Pair<Rule>) -> String {
-    input
-        .as_str()
-        .trim_start_matches("#base")
-        .trim_matches(|p: char| p == '"' || p.is_ascii_whitespace())
-        .to_string()
+pub(crate) fn walk_pair<V>(input: Pair<Rule>, visitor: &mut V) -> V::Output
+where
+    V: PairVisitor,
+{
+    // the grammar guarantees a key followed by a value or a section, comments aside
+    let mut inner = input
+        .clone()
+        .into_inner()
+        .filter(|p| matches!(p.as_rule(), Rule::key | Rule::value | Rule::section));
+
+    let key = inner.next().unwrap();
+    let value = inner.next().unwrap();
+
+    match value.as_rule() {
+        Rule::section => {
+            let children = value
+                .clone()
+                .into_inner()
+                .filter(|p| p.as_rule() == Rule::keyvalue)
+                .map(|p| walk_pair(p, visitor))
+                .collect();
+
+            visitor.visit_section(&input, &key, &value, children)
+        }
+        _ => visitor.visit_value(&input, &key, &value),
+    }
+}
+
+struct KvBuilder;
+
+impl PairVisitor for KvBuilder {
+    type Output = KeyValue;
+
+    fn visit_value(&mut self, _: &Pair<Rule>, key: &Pair<Rule>, value: &Pair<Rule>) -> KeyValue {
+        KeyValue {
+            key: unquote(key).to_string(),
+            value: Value::Value(unquote(value).to_string()),
+        }
+    }
+
+    fn visit_section(
+        &mut self,
+        _: &Pair<Rule>,
+        key: &Pair<Rule>,
+        _: &Pair<Rule>,
+        children: Vec<KeyValue>,
+    ) -> KeyValue {
+        KeyValue {
+            key: unquote(key).to_string(),
+            value: Value::Section(children),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Location {
+    // byte offset into the input
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpannedValue {
+    Value(String),
+    Section(Vec<SpannedKeyValue>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedKeyValue {
+    pub key: String,
+    pub value: SpannedValue,
+    // the whole pair, from the opening quote of the key to the closing quote or brace
+    pub span: Span,
+    // quotes and braces included
+    pub key_span: Span,
+    pub value_span: Span,
+}
+
+impl SpannedKeyValue {
+    pub fn to_kv(&self) -> KeyValue {
+        let value = match &self.value {
+            SpannedValue::Value(v) => Value::Value(v.clone()),
+            SpannedValue::Section(section) => {
+                Value::Section(section.iter().map(SpannedKeyValue::to_kv).collect())
+            }
+        };
+
+        KeyValue {
+            key: self.key.clone(),
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpannedFile {
+    pub imports: Vec<(String, Span)>,
+    pub kvs: Vec<SpannedKeyValue>,
+}
+
+impl SpannedFile {
+    pub fn to_file(&self) -> KeyValueFile {
+        KeyValueFile {
+            imports: self.imports.iter().map(|(path, _)| path.clone()).collect(),
+            kvs: self.kvs.iter().map(SpannedKeyValue::to_kv).collect(),
+        }
+    }
+}
+
+// same as parse_input, but every pair remembers where it came from
+pub fn parse_input_spanned(input: &str) -> Result<SpannedFile, Error> {
+    let mut builder = SpannedBuilder {
+        lines: LineIndex::new(input),
+    };
+
+    let (imports, kvs) = walk_input(input, &mut builder)?;
+
+    Ok(SpannedFile {
+        imports: imports
+            .into_iter()
+            .map(|pair| {
+                let span = builder.lines.span(&pair);
+                (parse_import(pair), span)
+            })
+            .collect(),
+        kvs,
+    })
+}
+
+// the first pair at `path` in document order, every occurrence of a repeated key is searched,
+// the rest of the input is checked by the grammar but never turned into values
+// keys match in any case, see key_matches
+pub fn parse_input_at(input: &str, path: &KeyPath) -> Result<Option<SpannedKeyValue>, Error> {
+    let pairs = KeyValueParser::parse(Rule::file, input)
+        .map_err(|e| Error::ParseKeyValueError(Box::new(e)))?;
+
+    let mut builder = SpannedBuilder {
+        lines: LineIndex::new(input),
+    };
+
+    for pair_outer in pairs {
+        if let Rule::file = pair_outer.as_rule() {
+            if let Some(pair) = find_pair(pair_outer.into_inner(), path.0.as_slice()) {
+                return Ok(Some(walk_pair(pair, &mut builder)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// how a segment of a path finds its key, in any case like the engine reads them
+pub(crate) fn key_matches(key: &str, segment: &str) -> bool {
+    key.eq_ignore_ascii_case(segment)
+}
+
+fn find_pair<'i>(pairs: Pairs<'i, Rule>, path: &[String]) -> Option<Pair<'i, Rule>> {
+    let (first, rest) = path.split_first()?;
+
+    for pair in pairs.filter(|p| p.as_rule() == Rule::keyvalue) {
+        let mut inner = pair.clone().into_inner();
+
+        let key = inner.find(|p| p.as_rule() == Rule::key)?;
+
+        if !key_matches(unquote(&key), first) {
+            continue;
+        }
+
+        if rest.is_empty() {
+            return Some(pair);
+        }
+
+        let found = inner
+            .find(|p| p.as_rule() == Rule::section)
+            .and_then(|section| find_pair(section.into_inner(), rest));
+
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    None
+}
+
+struct SpannedBuilder<'a> {
+    lines: LineIndex<'a>,
 }
 
-fn parse_single_kv(input: Pair<Rule>) -> KeyValue {
-    let mut kv = KeyValue::default();
+impl PairVisitor for SpannedBuilder<'_> {
+    type Output = SpannedKeyValue;
+
+    fn visit_value(
+        &mut self,
+        pair: &Pair<Rule>,
+        key: &Pair<Rule>,
+        value: &Pair<Rule>,
+    ) -> SpannedKeyValue {
+        SpannedKeyValue {
+            key: unquote(key).to_string(),
+            value: SpannedValue::Value(unquote(value).to_string()),
+            span: self.lines.span(pair),
+            key_span: self.lines.span(key),
+            value_span: self.lines.span(value),
+        }
+    }
 
-    for pair in input.into_inner() {
-        match pair.as_rule() {
-            Rule::key => kv.key = pair.as_str().trim_matches('"').to_string(),
-            Rule::value => kv.value = Value::Value(pair.as_str().trim_matches('"').to_string()),
-            Rule::section => kv.value = Value::Section(parse_section(pair.into_inner())),
-            _ => (),
+    fn visit_section(
+        &mut self,
+        pair: &Pair<Rule>,
+        key: &Pair<Rule>,
+        section: &Pair<Rule>,
+        children: Vec<SpannedKeyValue>,
+    ) -> SpannedKeyValue {
+        SpannedKeyValue {
+            key: unquote(key).to_string(),
+            value: SpannedValue::Section(children),
+            span: self.lines.span(pair),
+            key_span: self.lines.span(key),
+            value_span: self.lines.span(section),
         }
     }
+}
 
-    kv
+// pest's own line_col rescans the input on every call
+struct LineIndex<'a> {
+    input: &'a str,
+    starts: Vec<usize>,
 }
 
-fn parse_section(input: Pairs<Rule>) -> Vec<KeyValue> {
-    let mut kvs = Vec::new();
+impl<'a> LineIndex<'a> {
+    fn new(input: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        LineIndex { input, starts }
+    }
+
+    fn location(&self, offset: usize) -> Location {
+        let line = self.starts.partition_point(|&start| start <= offset);
+        let line_start = self.starts[line - 1];
 
-    for pair in input {
-        if let Rule::keyvalue = pair.as_rule() {
-            kvs.push(parse_single_kv(pair));
+        Location {
+            offset,
+            line,
+            column: self.input[line_start..offset].chars().count() + 1,
         }
     }
 
-    kvs
+    fn span(&self, pair: &Pair<Rule>) -> Span {
+        let span = pair.as_span();
+
+        Span {
+            start: self.location(span.start()),
+            end: self.location(span.end()),
+        }
+    }
 }
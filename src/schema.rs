@@ -0,0 +1,422 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{
+    error::Error,
+    kv::{KeyPath, KeyValue, Value},
+    parser::{parse_file, parse_input},
+};
+
+mod infer;
+mod json;
+mod trace;
+
+pub use infer::{infer_dir, InferOptions, NodeStats, SchemaInferrer};
+pub use json::json_schema_for;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaType {
+    #[default]
+    Any,
+    String,
+    Int,
+    Float,
+    Bool,
+    Enum,
+    Section,
+}
+
+impl SchemaType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchemaType::Any => "any",
+            SchemaType::String => "string",
+            SchemaType::Int => "int",
+            SchemaType::Float => "float",
+            SchemaType::Bool => "bool",
+            SchemaType::Enum => "enum",
+            SchemaType::Section => "section",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<SchemaType> {
+        [
+            SchemaType::Any,
+            SchemaType::String,
+            SchemaType::Int,
+            SchemaType::Float,
+            SchemaType::Bool,
+            SchemaType::Enum,
+            SchemaType::Section,
+        ]
+        .into_iter()
+        .find(|t| t.name() == name)
+    }
+}
+
+// a schema is itself written in KV:
+//
+// "type" "section"
+// "children"
+// {
+//     "AbilityCooldown" { "type" "float" "min" "0" "required" "1" }
+//     "AbilityType" { "type" "enum" "values" { "0" "ULTIMATE" "1" "BASIC" } }
+//     "Shape" { "type" "enum" "variants" { "Circle" { "type" "float" } } }
+// }
+// "other" { "type" "string" }
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+    pub value_type: SchemaType,
+    pub required: bool,
+    pub repeated: bool,
+    pub values: Vec<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub children: Vec<(String, Schema)>,
+    // applies to children missing from `children`
+    pub other: Option<Box<Schema>>,
+    // rejects children missing from `children` when there is no `other`
+    pub closed: bool,
+    // payloads of data carrying enum variants, written as a section holding only the variant key
+    pub variants: Vec<(String, Schema)>,
+    // also accepts "", which is how a None is written
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    MissingKey,
+    UnexpectedKey,
+    DuplicateKey,
+    ExpectedSection,
+    ExpectedValue,
+    InvalidType(SchemaType),
+    NotInEnum,
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub path: KeyPath,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match &self.kind {
+            ViolationKind::MissingKey => "missing required key".to_string(),
+            ViolationKind::UnexpectedKey => "unexpected key".to_string(),
+            ViolationKind::DuplicateKey => "duplicate key".to_string(),
+            ViolationKind::ExpectedSection => "expected a section".to_string(),
+            ViolationKind::ExpectedValue => "expected a value".to_string(),
+            ViolationKind::InvalidType(t) => format!("expected {}", t.name()),
+            ViolationKind::NotInEnum => "value is not one of the allowed values".to_string(),
+            ViolationKind::OutOfRange => "value is out of range".to_string(),
+        };
+
+        write!(f, "{}: {}", self.path, message)
+    }
+}
+
+impl FromStr for Schema {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Schema::from_kvs(&parse_input(s)?.kvs)
+    }
+}
+
+impl Schema {
+    pub fn from_file(path: &str) -> Result<Schema, Error> {
+        Schema::from_kvs(&parse_file(path)?)
+    }
+
+    pub fn from_kvs(kvs: &[KeyValue]) -> Result<Schema, Error> {
+        let mut schema = Schema::default();
+
+        for kv in kvs {
+            match (kv.key.as_str(), &kv.value) {
+                ("type", Value::Value(v)) => {
+                    schema.value_type = SchemaType::from_name(v)
+                        .ok_or_else(|| invalid_schema(&format!("unknown type {}", v)))?
+                }
+                ("required", Value::Value(v)) => schema.required = parse_flag(&kv.key, v)?,
+                ("repeated", Value::Value(v)) => schema.repeated = parse_flag(&kv.key, v)?,
+                ("closed", Value::Value(v)) => schema.closed = parse_flag(&kv.key, v)?,
+                ("nullable", Value::Value(v)) => schema.nullable = parse_flag(&kv.key, v)?,
+                ("min", Value::Value(v)) => schema.min = Some(parse_number(&kv.key, v)?),
+                ("max", Value::Value(v)) => schema.max = Some(parse_number(&kv.key, v)?),
+                ("values", Value::Section(section)) => {
+                    for value in section {
+                        match &value.value {
+                            Value::Value(v) => schema.values.push(v.clone()),
+                            Value::Section(_) => {
+                                return Err(invalid_schema("values must be values"))
+                            }
+                        }
+                    }
+                }
+                ("children", Value::Section(section)) => {
+                    schema.children = from_schema_kvs(section)?
+                }
+                ("variants", Value::Section(section)) => {
+                    schema.variants = from_schema_kvs(section)?
+                }
+                ("other", Value::Section(section)) => {
+                    schema.other = Some(Box::new(Schema::from_kvs(section)?))
+                }
+                (key, _) => return Err(invalid_schema(&format!("unexpected key {}", key))),
+            }
+        }
+
+        Ok(schema)
+    }
+
+    pub fn to_kvs(&self) -> Vec<KeyValue> {
+        let value = |key: &str, value: String| KeyValue {
+            key: key.to_string(),
+            value: Value::Value(value),
+        };
+        let flag = |key: &str| value(key, "1".to_string());
+
+        let mut kvs = vec![value("type", self.value_type.name().to_string())];
+
+        if self.required {
+            kvs.push(flag("required"));
+        }
+
+        if self.repeated {
+            kvs.push(flag("repeated"));
+        }
+
+        if !self.values.is_empty() {
+            kvs.push(KeyValue {
+                key: "values".to_string(),
+                value: Value::Section(
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(index, v)| value(&index.to_string(), v.clone()))
+                        .collect(),
+                ),
+            });
+        }
+
+        if let Some(min) = self.min {
+            kvs.push(value("min", min.to_string()));
+        }
+
+        if let Some(max) = self.max {
+            kvs.push(value("max", max.to_string()));
+        }
+
+        if !self.children.is_empty() {
+            kvs.push(to_schema_kvs("children", &self.children));
+        }
+
+        if !self.variants.is_empty() {
+            kvs.push(to_schema_kvs("variants", &self.variants));
+        }
+
+        if let Some(other) = &self.other {
+            kvs.push(KeyValue {
+                key: "other".to_string(),
+                value: Value::Section(other.to_kvs()),
+            });
+        }
+
+        if self.closed {
+            kvs.push(flag("closed"));
+        }
+
+        if self.nullable {
+            kvs.push(flag("nullable"));
+        }
+
+        kvs
+    }
+
+    pub fn child(&self, key: &str) -> Option<&Schema> {
+        self.children
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, schema)| schema)
+    }
+
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        self.validate_value(value, &mut KeyPath::new(), &mut violations);
+
+        violations
+    }
+
+    pub fn validate_kvs(&self, kvs: &[KeyValue]) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        self.validate_section(kvs, &mut KeyPath::new(), &mut violations);
+
+        violations
+    }
+
+    fn validate_value(&self, value: &Value, path: &mut KeyPath, violations: &mut Vec<Violation>) {
+        let kind = match (self.value_type, value) {
+            _ if self.nullable && value.as_str() == Some("") => None,
+            (SchemaType::Enum, Value::Section(section)) if !self.variants.is_empty() => {
+                self.validate_variant(section, path, violations);
+                None
+            }
+            (SchemaType::Any | SchemaType::Section, Value::Section(section)) => {
+                self.validate_section(section, path, violations);
+                None
+            }
+            (SchemaType::Any | SchemaType::String, Value::Value(_)) => None,
+            (SchemaType::Section, Value::Value(_)) => Some(ViolationKind::ExpectedSection),
+            (_, Value::Section(_)) => Some(ViolationKind::ExpectedValue),
+            (SchemaType::Bool, Value::Value(v)) => {
+                (v != "0" && v != "1").then_some(ViolationKind::InvalidType(SchemaType::Bool))
+            }
+            (SchemaType::Enum, Value::Value(v)) => {
+                (!self.values.contains(v)).then_some(ViolationKind::NotInEnum)
+            }
+            (value_type @ (SchemaType::Int | SchemaType::Float), Value::Value(v)) => {
+                let number = if value_type == SchemaType::Int {
+                    v.parse::<i64>().ok().map(|n| n as f64)
+                } else {
+                    v.parse::<f64>().ok()
+                };
+
+                match number {
+                    None => Some(ViolationKind::InvalidType(value_type)),
+                    Some(n) => (self.min.is_some_and(|min| n < min)
+                        || self.max.is_some_and(|max| n > max))
+                    .then_some(ViolationKind::OutOfRange),
+                }
+            }
+        };
+
+        if let Some(kind) = kind {
+            violations.push(Violation {
+                path: path.clone(),
+                kind,
+            });
+        }
+    }
+
+    fn validate_variant(
+        &self,
+        section: &[KeyValue],
+        path: &mut KeyPath,
+        violations: &mut Vec<Violation>,
+    ) {
+        let [kv] = section else {
+            violations.push(Violation {
+                path: path.clone(),
+                kind: ViolationKind::NotInEnum,
+            });
+            return;
+        };
+
+        path.push(&kv.key);
+
+        match self.variants.iter().find(|(name, _)| name == &kv.key) {
+            Some((_, schema)) => schema.validate_value(&kv.value, path, violations),
+            None => violations.push(Violation {
+                path: path.clone(),
+                kind: ViolationKind::NotInEnum,
+            }),
+        }
+
+        path.pop();
+    }
+
+    fn validate_section(
+        &self,
+        section: &[KeyValue],
+        path: &mut KeyPath,
+        violations: &mut Vec<Violation>,
+    ) {
+        let mut occurrences: HashMap<&str, usize> = HashMap::new();
+
+        for kv in section {
+            let occurrence = occurrences.entry(&kv.key).or_default();
+            *occurrence += 1;
+
+            path.push(&kv.key);
+
+            match self.child(&kv.key).or(self.other.as_deref()) {
+                Some(schema) => {
+                    if *occurrence > 1 && !schema.repeated {
+                        violations.push(Violation {
+                            path: path.clone(),
+                            kind: ViolationKind::DuplicateKey,
+                        });
+                    }
+
+                    schema.validate_value(&kv.value, path, violations);
+                }
+                None if self.closed => violations.push(Violation {
+                    path: path.clone(),
+                    kind: ViolationKind::UnexpectedKey,
+                }),
+                None => (),
+            }
+
+            path.pop();
+        }
+
+        for (key, schema) in &self.children {
+            if schema.required && !occurrences.contains_key(key.as_str()) {
+                violations.push(Violation {
+                    path: path.join(key),
+                    kind: ViolationKind::MissingKey,
+                });
+            }
+        }
+    }
+}
+
+fn from_schema_kvs(section: &[KeyValue]) -> Result<Vec<(String, Schema)>, Error> {
+    section
+        .iter()
+        .map(|kv| match &kv.value {
+            Value::Section(schema) => Ok((kv.key.clone(), Schema::from_kvs(schema)?)),
+            Value::Value(_) => Err(invalid_schema(&format!(
+                "child {} must be a section",
+                kv.key
+            ))),
+        })
+        .collect()
+}
+
+fn to_schema_kvs(key: &str, schemas: &[(String, Schema)]) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Value::Section(
+            schemas
+                .iter()
+                .map(|(key, schema)| KeyValue {
+                    key: key.clone(),
+                    value: Value::Section(schema.to_kvs()),
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn invalid_schema(message: &str) -> Error {
+    Error::InvalidSchemaError(message.to_string())
+}
+
+fn parse_flag(key: &str, value: &str) -> Result<bool, Error> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(invalid_schema(&format!("{} must be 0 or 1", key))),
+    }
+}
+
+fn parse_number(key: &str, value: &str) -> Result<f64, Error> {
+    value
+        .parse()
+        .map_err(|_| invalid_schema(&format!("{} must be a number", key)))
+}
@@ -0,0 +1,317 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Error,
+    kv::{KeyValue, Value},
+    parser::{parse_file, parse_input},
+    schema::{Schema, SchemaType},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferOptions {
+    // string values with at most this many distinct values become enums
+    pub enum_limit: usize,
+    // sections with more distinct child keys than this are described by a single `other` schema
+    pub map_threshold: usize,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        InferOptions {
+            enum_limit: 16,
+            map_threshold: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeStats {
+    // number of parent sections containing the key at least once
+    pub present: usize,
+    pub occurrences: usize,
+    // highest number of occurrences inside a single parent section
+    pub max_repeat: usize,
+    pub sections: usize,
+    pub values: usize,
+    pub bools: usize,
+    pub ints: usize,
+    // ints included
+    pub numbers: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub distinct: BTreeMap<String, usize>,
+    // set once more than `enum_limit` distinct values were seen, `distinct` stops growing then
+    pub distinct_overflow: bool,
+    pub children: Vec<(String, NodeStats)>,
+    index: HashMap<String, usize>,
+}
+
+impl NodeStats {
+    pub fn child(&self, key: &str) -> Option<&NodeStats> {
+        self.index.get(key).map(|&i| &self.children[i].1)
+    }
+
+    // share of the sections of this node that contain the key
+    pub fn frequency(&self, key: &str) -> f64 {
+        match self.child(key) {
+            Some(child) if self.sections > 0 => child.present as f64 / self.sections as f64,
+            _ => 0.0,
+        }
+    }
+
+    fn child_mut(&mut self, key: &str) -> &mut NodeStats {
+        let index = match self.index.get(key) {
+            Some(&index) => index,
+            None => {
+                self.children.push((key.to_string(), NodeStats::default()));
+                self.index.insert(key.to_string(), self.children.len() - 1);
+                self.children.len() - 1
+            }
+        };
+
+        &mut self.children[index].1
+    }
+
+    fn add_value(&mut self, value: &Value, options: &InferOptions) {
+        match value {
+            Value::Section(section) => {
+                self.sections += 1;
+                self.add_section(section, options);
+            }
+            Value::Value(v) => {
+                self.values += 1;
+
+                if v == "0" || v == "1" {
+                    self.bools += 1;
+                }
+
+                let number = if let Ok(n) = v.parse::<i64>() {
+                    self.ints += 1;
+                    Some(n as f64)
+                } else {
+                    v.parse::<f64>().ok().filter(|n| n.is_finite())
+                };
+
+                if let Some(n) = number {
+                    self.numbers += 1;
+                    self.min = Some(self.min.map_or(n, |min| min.min(n)));
+                    self.max = Some(self.max.map_or(n, |max| max.max(n)));
+                }
+
+                self.add_distinct(v, options);
+            }
+        }
+    }
+
+    fn add_distinct(&mut self, value: &str, options: &InferOptions) {
+        if let Some(count) = self.distinct.get_mut(value) {
+            *count += 1;
+        } else if self.distinct.len() < options.enum_limit {
+            self.distinct.insert(value.to_string(), 1);
+        } else {
+            self.distinct_overflow = true;
+        }
+    }
+
+    fn add_section(&mut self, kvs: &[KeyValue], options: &InferOptions) {
+        let mut repeats: HashMap<&str, usize> = HashMap::new();
+
+        for kv in kvs {
+            *repeats.entry(&kv.key).or_default() += 1;
+
+            let child = self.child_mut(&kv.key);
+            child.occurrences += 1;
+            child.add_value(&kv.value, options);
+        }
+
+        for (key, repeat) in repeats {
+            let child = self.child_mut(key);
+            child.present += 1;
+            child.max_repeat = child.max_repeat.max(repeat);
+        }
+    }
+
+    fn absorb(&mut self, other: &NodeStats, options: &InferOptions) {
+        self.present += other.present;
+        self.occurrences += other.occurrences;
+        self.max_repeat = self.max_repeat.max(other.max_repeat);
+        self.sections += other.sections;
+        self.values += other.values;
+        self.bools += other.bools;
+        self.ints += other.ints;
+        self.numbers += other.numbers;
+        self.min = other.min.into_iter().chain(self.min).reduce(f64::min);
+        self.max = other.max.into_iter().chain(self.max).reduce(f64::max);
+        self.distinct_overflow |= other.distinct_overflow;
+
+        for (value, count) in &other.distinct {
+            for _ in 0..*count {
+                self.add_distinct(value, options);
+            }
+        }
+
+        for (key, child) in &other.children {
+            self.child_mut(key).absorb(child, options);
+        }
+    }
+
+    fn to_schema(&self, parent_sections: usize, options: &InferOptions) -> Schema {
+        let mut schema = Schema {
+            required: parent_sections > 0 && self.present == parent_sections,
+            repeated: self.max_repeat > 1,
+            ..Default::default()
+        };
+
+        if self.sections > 0 && self.values == 0 {
+            schema.value_type = SchemaType::Section;
+
+            // keys never shared between two samples are names rather than fields
+            let unique_keys = self.sections > 1
+                && self.children.len() > 1
+                && self.children.iter().all(|(_, child)| child.present == 1);
+
+            if unique_keys || self.children.len() > options.map_threshold {
+                let mut other = NodeStats::default();
+
+                for (_, child) in &self.children {
+                    other.absorb(child, options);
+                }
+
+                let mut other = other.to_schema(self.sections, options);
+                other.required = false;
+
+                schema.other = Some(Box::new(other));
+            } else {
+                schema.children = self
+                    .children
+                    .iter()
+                    .map(|(key, child)| (key.clone(), child.to_schema(self.sections, options)))
+                    .collect();
+            }
+        } else if self.values > 0 && self.sections == 0 {
+            if self.bools == self.values {
+                schema.value_type = SchemaType::Bool;
+            } else if self.ints == self.values || self.numbers == self.values {
+                schema.value_type = if self.ints == self.values {
+                    SchemaType::Int
+                } else {
+                    SchemaType::Float
+                };
+                schema.min = self.min;
+                schema.max = self.max;
+            } else if !self.distinct_overflow && self.values > self.distinct.len() {
+                schema.value_type = SchemaType::Enum;
+                schema.values = self.distinct.keys().cloned().collect();
+            } else {
+                schema.value_type = SchemaType::String;
+            }
+        }
+
+        schema
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaInferrer {
+    options: InferOptions,
+    root: NodeStats,
+    // the error of every file add_dir couldn't read as KV text
+    skipped: Vec<(PathBuf, String)>,
+}
+
+impl SchemaInferrer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: InferOptions) -> Self {
+        SchemaInferrer {
+            options,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_kvs(&mut self, kvs: &[KeyValue]) {
+        self.root.present += 1;
+        self.root.occurrences += 1;
+        self.root.max_repeat = 1;
+        self.root.sections += 1;
+        self.root.add_section(kvs, &self.options);
+    }
+
+    pub fn add_value(&mut self, value: &Value) {
+        match value {
+            Value::Section(section) => self.add_kvs(section),
+            Value::Value(_) => {
+                self.root.present += 1;
+                self.root.add_value(value, &self.options);
+            }
+        }
+    }
+
+    pub fn add_str(&mut self, input: &str) -> Result<(), Error> {
+        self.add_kvs(&parse_input(input)?.kvs);
+        Ok(())
+    }
+
+    // follows #base imports like parser::parse_file
+    pub fn add_file(&mut self, path: &str) -> Result<(), Error> {
+        self.add_kvs(&parse_file(path)?);
+        Ok(())
+    }
+
+    // every file below the directory is one sample, imports are not followed so shared bases aren't counted twice,
+    // files that aren't KV text (READMEs, .DS_Store, binaries) are left out and reported by `skipped`
+    pub fn add_dir(&mut self, path: &str) -> Result<(), Error> {
+        let mut entries = fs::read_dir(path)
+            .map_err(Error::ReadFileError)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::ReadFileError)?;
+
+        entries.sort();
+
+        for entry in entries {
+            if entry.is_dir() {
+                self.add_dir(&entry.to_string_lossy())?;
+            } else if let Err(error) = self.add_dir_file(&entry) {
+                self.skipped.push((entry, error.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_dir_file(&mut self, path: &Path) -> Result<(), Error> {
+        let file = String::from_utf8(fs::read(path).map_err(Error::ReadFileError)?)
+            .map_err(Error::ReadUtf8Error)?;
+
+        self.add_str(&file)
+    }
+
+    pub fn skipped(&self) -> &[(PathBuf, String)] {
+        &self.skipped
+    }
+
+    pub fn stats(&self) -> &NodeStats {
+        &self.root
+    }
+
+    pub fn infer(&self) -> Schema {
+        let mut schema = self.root.to_schema(self.root.present, &self.options);
+        schema.required = false;
+        schema.repeated = false;
+        schema
+    }
+}
+
+pub fn infer_dir(path: &str) -> Result<Schema, Error> {
+    let mut inferrer = SchemaInferrer::new();
+    inferrer.add_dir(path)?;
+
+    Ok(inferrer.infer())
+}
@@ -0,0 +1,120 @@
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map};
+
+use crate::{
+    error::Error,
+    schema::{Schema, SchemaType},
+};
+
+const INT_PATTERN: &str = r"^[+-]?[0-9]+$";
+const FLOAT_PATTERN: &str = r"^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$";
+
+impl Schema {
+    // describes the JSON produced by Value's Serialize impl, where every value is a string
+    // and every section an object, so numeric ranges can't be expressed
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let mut root = self.to_json_node();
+
+        if let serde_json::Value::Object(object) = &mut root {
+            object.insert(
+                "$schema".to_string(),
+                json!("https://json-schema.org/draft/2020-12/schema"),
+            );
+        }
+
+        root
+    }
+
+    fn to_json_node(&self) -> serde_json::Value {
+        let node = self.to_json_type();
+
+        if self.nullable {
+            json!({ "anyOf": [node, { "const": "" }] })
+        } else {
+            node
+        }
+    }
+
+    fn to_json_type(&self) -> serde_json::Value {
+        match self.value_type {
+            SchemaType::Enum if !self.variants.is_empty() => self.to_json_variants(),
+            SchemaType::String => json!({ "type": "string" }),
+            SchemaType::Int => json!({ "type": "string", "pattern": INT_PATTERN }),
+            SchemaType::Float => json!({ "type": "string", "pattern": FLOAT_PATTERN }),
+            SchemaType::Bool => json!({ "type": "string", "enum": ["0", "1"] }),
+            SchemaType::Enum => json!({ "type": "string", "enum": self.values }),
+            SchemaType::Section => serde_json::Value::Object(self.to_json_object()),
+            SchemaType::Any => {
+                let mut object = self.to_json_object();
+                object.insert("type".to_string(), json!(["string", "object"]));
+
+                serde_json::Value::Object(object)
+            }
+        }
+    }
+
+    // unit variants are plain strings, a data carrying variant an object with only its name as key
+    fn to_json_variants(&self) -> serde_json::Value {
+        let mut one_of = vec![];
+
+        if !self.values.is_empty() {
+            one_of.push(json!({ "type": "string", "enum": self.values }));
+        }
+
+        for (name, schema) in &self.variants {
+            one_of.push(json!({
+                "type": "object",
+                "properties": { name.as_str(): schema.to_json_node() },
+                "required": [name],
+                "additionalProperties": false,
+            }));
+        }
+
+        json!({ "oneOf": one_of })
+    }
+
+    fn to_json_object(&self) -> Map<String, serde_json::Value> {
+        let mut object = Map::new();
+
+        object.insert("type".to_string(), json!("object"));
+
+        if !self.children.is_empty() {
+            let properties: Map<String, serde_json::Value> = self
+                .children
+                .iter()
+                .map(|(key, schema)| (key.clone(), schema.to_json_node()))
+                .collect();
+
+            let required: Vec<&String> = self
+                .children
+                .iter()
+                .filter(|(_, schema)| schema.required)
+                .map(|(key, _)| key)
+                .collect();
+
+            object.insert(
+                "properties".to_string(),
+                serde_json::Value::Object(properties),
+            );
+
+            if !required.is_empty() {
+                object.insert("required".to_string(), json!(required));
+            }
+        }
+
+        if let Some(other) = &self.other {
+            object.insert("additionalProperties".to_string(), other.to_json_node());
+        } else if self.closed {
+            object.insert("additionalProperties".to_string(), json!(false));
+        }
+
+        object
+    }
+}
+
+pub fn json_schema_for<T>() -> Result<serde_json::Value, Error>
+where
+    T: DeserializeOwned,
+{
+    Ok(Schema::from_type::<T>()?.to_json_schema())
+}
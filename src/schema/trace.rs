@@ -0,0 +1,834 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess,
+};
+
+use crate::{
+    error::Error,
+    schema::{Schema, SchemaType},
+};
+
+// deeper options, sequences and maps are traced as empty, which stops recursive types
+const MAX_DEPTH: usize = 32;
+
+impl Schema {
+    // traces the shape serde expects for T, T is traced once per variant of its largest enum so
+    // every variant payload is seen
+    // leaves are fed placeholders like 0 and "", a type that rejects them, like NonZeroU32 or
+    // Ipv4Addr, keeps the shape traced so far and its field is left out of the following passes
+    // fields are required unless the struct can do without them, which the passes in probing
+    // mode find out by leaving out every field not known to be required, see TraceState::learn
+    pub fn from_type<T>() -> Result<Schema, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut schema = Schema::default();
+        let state = TraceState::default();
+
+        let pass = |schema: &mut Schema| {
+            state.rejected.set(false);
+            state.changed.set(false);
+
+            let res = T::deserialize(Tracer {
+                schema,
+                depth: 0,
+                state: &state,
+            });
+
+            match res {
+                Err(e) if !state.rejected.get() => Err(e),
+                _ => Ok(state.changed.get()),
+            }
+        };
+
+        loop {
+            // probed structs fail until every required field is known, so errors are expected
+            state.probing.set(true);
+
+            loop {
+                let _ = pass(&mut schema);
+
+                if !state.changed.get() {
+                    break;
+                }
+            }
+
+            state.probing.set(false);
+
+            while pass(&mut schema)? {}
+
+            if !state.advance() {
+                break;
+            }
+
+            // fields may hold other variants now
+            state.skipped.borrow_mut().clear();
+        }
+
+        schema.required = false;
+
+        Ok(schema)
+    }
+}
+
+// enums are told apart by their name and variants, structs by their name and fields
+type EnumKey = (&'static str, &'static [&'static str]);
+type StructKey = (&'static str, &'static [&'static str]);
+
+// the fields a struct is known to fail without, in the order serde reports them
+#[derive(Default)]
+struct Probe {
+    required: Vec<&'static str>,
+    optional: Vec<&'static str>,
+    // the struct succeeded with only the required fields
+    done: bool,
+}
+
+// the variant every enum takes in the current pass, tracing writes into the same schema
+// on every pass, so struct fields and variants are looked up before being added
+#[derive(Default)]
+struct TraceState {
+    choices: RefCell<HashMap<EnumKey, usize>>,
+    seen: RefCell<Vec<EnumKey>>,
+    active: RefCell<Vec<EnumKey>>,
+    // a placeholder was rejected in this pass, or a struct failed for a field left out because of it
+    rejected: Cell<bool>,
+    // fields left out because their placeholder was rejected
+    skipped: RefCell<Vec<(StructKey, &'static str)>>,
+    probing: Cell<bool>,
+    probes: RefCell<HashMap<StructKey, Probe>>,
+    // a pass skipped a field or learned about one
+    changed: Cell<bool>,
+}
+
+impl TraceState {
+    fn choose(&self, key: EnumKey) -> usize {
+        // an enum nested in itself takes its first variant, so the payload doesn't recurse forever
+        if self.active.borrow().contains(&key) {
+            return 0;
+        }
+
+        if !self.seen.borrow().contains(&key) {
+            self.seen.borrow_mut().push(key);
+        }
+
+        *self.choices.borrow_mut().entry(key).or_default()
+    }
+
+    fn placeholder<T>(&self, res: Result<T, Error>) -> Result<T, Error> {
+        if res.is_err() {
+            self.rejected.set(true);
+        }
+
+        res
+    }
+
+    fn is_skipped(&self, key: StructKey, field: &str) -> bool {
+        self.skipped
+            .borrow()
+            .iter()
+            .any(|(k, f)| *k == key && *f == field)
+    }
+
+    fn skip(&self, key: StructKey, field: &'static str) {
+        if !self.is_skipped(key, field) {
+            self.skipped.borrow_mut().push((key, field));
+            self.changed.set(true);
+        }
+    }
+
+    fn omits(&self, key: StructKey, field: &str) -> bool {
+        if self.is_skipped(key, field) {
+            return true;
+        }
+
+        // a finished struct shows every field so the structs inside get probed too
+        self.probing.get()
+            && !self
+                .probes
+                .borrow()
+                .get(&key)
+                .is_some_and(|probe| probe.done || probe.required.contains(&field))
+    }
+
+    // what a probed struct reports when left with only its known required fields
+    // serde reports the first missing field without a default in declaration order, so the
+    // fields left out before it have one, a required field that is skipped hides the rest
+    fn learn<T>(&self, key: StructKey, res: &Result<T, Error>) {
+        let mut probes = self.probes.borrow_mut();
+        let probe = probes.entry(key).or_default();
+
+        let missing = match res {
+            Ok(_) if !probe.done => {
+                probe.done = true;
+                self.changed.set(true);
+                return;
+            }
+            Err(Error::MissingFieldError(field)) if key.1.contains(field) => *field,
+            _ => return,
+        };
+
+        for &field in key.1.iter().take_while(|&&field| field != missing) {
+            let shown = probe.required.contains(&field) && !self.is_skipped(key, field);
+
+            if !shown && !probe.optional.contains(&field) {
+                probe.optional.push(field);
+                self.changed.set(true);
+            }
+        }
+
+        if !probe.required.contains(&missing) {
+            probe.required.push(missing);
+            self.changed.set(true);
+        }
+    }
+
+    // None while the probes haven't found out
+    fn required(&self, key: StructKey, field: &str) -> Option<bool> {
+        let probes = self.probes.borrow();
+        let probe = probes.get(&key)?;
+
+        if probe.required.contains(&field) {
+            Some(true)
+        } else if probe.done || probe.optional.contains(&field) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // moves every enum seen in the last pass to its next variant
+    fn advance(&self) -> bool {
+        let mut advanced = false;
+        let mut choices = self.choices.borrow_mut();
+
+        for key in self.seen.borrow_mut().drain(..) {
+            let choice = choices.entry(key).or_default();
+
+            if *choice + 1 < key.1.len() {
+                *choice += 1;
+                advanced = true;
+            }
+        }
+
+        advanced
+    }
+}
+
+struct Tracer<'a> {
+    schema: &'a mut Schema,
+    depth: usize,
+    state: &'a TraceState,
+}
+
+fn find_or_push<'a>(children: &'a mut Vec<(String, Schema)>, key: &str) -> &'a mut Schema {
+    let index = match children.iter().position(|(k, _)| k == key) {
+        Some(index) => index,
+        None => {
+            children.push((
+                key.to_string(),
+                Schema {
+                    required: true,
+                    ..Default::default()
+                },
+            ));
+            children.len() - 1
+        }
+    };
+
+    &mut children[index].1
+}
+
+impl Tracer<'_> {
+    fn number(&mut self, value_type: SchemaType, min: Option<f64>, max: Option<f64>) {
+        self.schema.value_type = value_type;
+        self.schema.min = min;
+        self.schema.max = max;
+    }
+
+    fn int<T>(&mut self, min: T, max: T)
+    where
+        T: Into<f64>,
+    {
+        self.number(SchemaType::Int, Some(min.into()), Some(max.into()));
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Tracer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Any;
+        self.state.placeholder(visitor.visit_str(""))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Bool;
+        self.state.placeholder(visitor.visit_bool(false))
+    }
+
+    fn deserialize_i8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(i8::MIN, i8::MAX);
+        self.state.placeholder(visitor.visit_i8(0))
+    }
+
+    fn deserialize_i16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(i16::MIN, i16::MAX);
+        self.state.placeholder(visitor.visit_i16(0))
+    }
+
+    fn deserialize_i32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(i32::MIN, i32::MAX);
+        self.state.placeholder(visitor.visit_i32(0))
+    }
+
+    fn deserialize_i64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.number(SchemaType::Int, None, None);
+        self.state.placeholder(visitor.visit_i64(0))
+    }
+
+    fn deserialize_u8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(u8::MIN, u8::MAX);
+        self.state.placeholder(visitor.visit_u8(0))
+    }
+
+    fn deserialize_u16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(u16::MIN, u16::MAX);
+        self.state.placeholder(visitor.visit_u16(0))
+    }
+
+    fn deserialize_u32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.int(u32::MIN, u32::MAX);
+        self.state.placeholder(visitor.visit_u32(0))
+    }
+
+    fn deserialize_u64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.number(SchemaType::Int, Some(0.0), None);
+        self.state.placeholder(visitor.visit_u64(0))
+    }
+
+    fn deserialize_f32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.number(SchemaType::Float, None, None);
+        self.state.placeholder(visitor.visit_f32(0.0))
+    }
+
+    fn deserialize_f64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.number(SchemaType::Float, None, None);
+        self.state.placeholder(visitor.visit_f64(0.0))
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::String;
+        self.state.placeholder(visitor.visit_char(' '))
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::String;
+        self.state.placeholder(visitor.visit_str(""))
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // a None is written as ""
+        self.schema.required = false;
+        self.schema.nullable = true;
+
+        if self.depth > MAX_DEPTH {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::String;
+        self.state.placeholder(visitor.visit_unit())
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Section;
+
+        let element = self.schema.other.get_or_insert_with(Default::default);
+
+        let res = visitor.visit_seq(SeqTracer {
+            schema: element,
+            remaining: usize::from(self.depth <= MAX_DEPTH),
+            depth: self.depth + 1,
+            state: self.state,
+        })?;
+
+        element.required = false;
+
+        Ok(res)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Section;
+
+        visitor.visit_seq(TupleTracer {
+            schema: self.schema,
+            len,
+            index: 0,
+            depth: self.depth + 1,
+            state: self.state,
+        })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Section;
+
+        let value = self.schema.other.get_or_insert_with(Default::default);
+
+        let res = visitor.visit_map(MapTracer {
+            schema: value,
+            remaining: usize::from(self.depth <= MAX_DEPTH),
+            depth: self.depth + 1,
+            state: self.state,
+        })?;
+
+        value.required = false;
+
+        Ok(res)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let state = self.state;
+        let key = (name, fields);
+        let field_failed = Cell::new(false);
+
+        self.schema.value_type = SchemaType::Section;
+
+        let res = visitor.visit_map(StructTracer {
+            schema: &mut *self.schema,
+            key,
+            index: 0,
+            depth: self.depth + 1,
+            state,
+            field_failed: &field_failed,
+        });
+
+        if let Err(Error::MissingFieldError(field)) = &res {
+            if state.is_skipped(key, field) {
+                state.rejected.set(true);
+            }
+        }
+
+        if state.probing.get() {
+            // a failed field says nothing about the fields the struct needs
+            if !field_failed.get() {
+                state.learn(key, &res);
+            }
+        } else {
+            for (field, schema) in &mut self.schema.children {
+                if let Some(required) = state.required(key, field) {
+                    schema.required = required;
+                }
+            }
+        }
+
+        res
+    }
+
+    // unit variants end up in `values` and data carrying variants in `variants`, one per pass
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let state = self.state;
+        let key = (name, variants);
+
+        // only an enum whose first variant contains itself gets here, its payload never ends
+        if self.depth > MAX_DEPTH && state.active.borrow().contains(&key) {
+            return Err(de::Error::custom(format!("{} is recursive", name)));
+        }
+
+        self.schema.value_type = SchemaType::Enum;
+        let choice = state.choose(key);
+
+        state.active.borrow_mut().push(key);
+
+        let res = visitor.visit_enum(EnumTracer {
+            variant: variants.get(choice).copied().unwrap_or_default(),
+            tracer: self,
+        });
+
+        state.active.borrow_mut().pop();
+
+        res
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.schema.value_type = SchemaType::Any;
+        self.state.placeholder(visitor.visit_unit())
+    }
+}
+
+struct SeqTracer<'a> {
+    schema: &'a mut Schema,
+    remaining: usize,
+    depth: usize,
+    state: &'a TraceState,
+}
+
+impl<'de> SeqAccess<'de> for SeqTracer<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+
+        seed.deserialize(Tracer {
+            schema: self.schema,
+            depth: self.depth,
+            state: self.state,
+        })
+        .map(Some)
+    }
+}
+
+struct TupleTracer<'a> {
+    schema: &'a mut Schema,
+    len: usize,
+    index: usize,
+    depth: usize,
+    state: &'a TraceState,
+}
+
+impl<'de> SeqAccess<'de> for TupleTracer<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+
+        let element = find_or_push(&mut self.schema.children, &self.index.to_string());
+        self.index += 1;
+
+        seed.deserialize(Tracer {
+            schema: element,
+            depth: self.depth,
+            state: self.state,
+        })
+        .map(Some)
+    }
+}
+
+struct MapTracer<'a> {
+    schema: &'a mut Schema,
+    remaining: usize,
+    depth: usize,
+    state: &'a TraceState,
+}
+
+impl<'de> MapAccess<'de> for MapTracer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+
+        seed.deserialize(Tracer {
+            schema: &mut Schema::default(),
+            depth: self.depth,
+            state: self.state,
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(Tracer {
+            schema: self.schema,
+            depth: self.depth,
+            state: self.state,
+        })
+    }
+}
+
+struct StructTracer<'a> {
+    schema: &'a mut Schema,
+    key: StructKey,
+    index: usize,
+    depth: usize,
+    state: &'a TraceState,
+    field_failed: &'a Cell<bool>,
+}
+
+impl<'de> MapAccess<'de> for StructTracer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let fields = self.key.1;
+
+        while fields
+            .get(self.index)
+            .is_some_and(|field| self.state.omits(self.key, field))
+        {
+            self.index += 1;
+        }
+
+        match fields.get(self.index) {
+            Some(field) => seed
+                .deserialize(IntoDeserializer::<Error>::into_deserializer(*field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = self.key.1[self.index];
+        let field = find_or_push(&mut self.schema.children, name);
+        self.index += 1;
+
+        let res = seed.deserialize(Tracer {
+            schema: field,
+            depth: self.depth,
+            state: self.state,
+        });
+
+        if res.is_err() {
+            self.field_failed.set(true);
+
+            if self.state.rejected.get() {
+                self.state.skip(self.key, name);
+            }
+        }
+
+        res
+    }
+}
+
+struct EnumTracer<'a> {
+    variant: &'static str,
+    tracer: Tracer<'a>,
+}
+
+impl EnumTracer<'_> {
+    // the payload schema of a data carrying variant, written as a section holding only the variant key
+    fn payload(&mut self) -> Tracer<'_> {
+        let schema = find_or_push(&mut self.tracer.schema.variants, self.variant);
+        schema.required = false;
+
+        Tracer {
+            schema,
+            depth: self.tracer.depth + 1,
+            state: self.tracer.state,
+        }
+    }
+}
+
+impl<'de> EnumAccess<'de> for EnumTracer<'_> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumTracer<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let values = &mut self.tracer.schema.values;
+
+        if !values.iter().any(|v| v == self.variant) {
+            values.push(self.variant.to_string());
+        }
+
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.payload())
+    }
+
+    fn tuple_variant<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple(self.payload(), len, visitor)
+    }
+
+    fn struct_variant<V>(
+        mut self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.payload(), "", fields, visitor)
+    }
+}
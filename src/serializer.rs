@@ -1,17 +1,36 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use serde::{
     ser::{self, Impossible},
     Serialize,
 };
 
-use crate::error::{Error, Result};
+use crate::{
+    canonical::{to_canonical_string, CanonicalOptions},
+    error::{Error, Result},
+    formatter::{format_value, FormatOptions},
+    kv::{KeyValue, Value},
+    parser::parse_input,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SerializerOptions {
+    // leaves out keys whose value is None instead of writing ""
+    pub omit_none: bool,
+}
 
 pub fn to_file<T>(value: &T) -> Result<String>
 where
     T: Serialize,
 {
-    let mut serializer = Serializer::new();
+    to_file_with_options(value, SerializerOptions::default())
+}
+
+pub fn to_file_with_options<T>(value: &T, options: SerializerOptions) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_options(options);
 
     value.serialize(&mut serializer)?;
     serializer.trim();
@@ -20,11 +39,36 @@ where
     Ok(serializer.output)
 }
 
+pub fn to_file_formatted<T>(value: &T, options: &FormatOptions) -> Result<String>
+where
+    T: Serialize,
+{
+    let value = value.serialize(ValueSerializer { omit_none: false })?;
+
+    Ok(format_value(&value_or_empty(value), options))
+}
+
+pub fn to_file_canonical<T>(value: &T, options: &CanonicalOptions) -> Result<String>
+where
+    T: Serialize,
+{
+    let file = parse_input(&to_file(value)?)?;
+
+    Ok(to_canonical_string(&file, options))
+}
+
 pub fn to_string<T>(value: &T) -> Result<String>
 where
     T: Serialize,
 {
-    let mut serializer = Serializer::new();
+    to_string_with_options(value, SerializerOptions::default())
+}
+
+pub fn to_string_with_options<T>(value: &T, options: SerializerOptions) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_options(options);
 
     value.serialize(&mut serializer)?;
     serializer.prettify();
@@ -32,19 +76,123 @@ where
     Ok(serializer.output)
 }
 
+// wraps the value in a single root key like "DOTAAbilities" { ... }
+pub fn to_string_rooted<T>(value: &T, root: &str) -> Result<String>
+where
+    T: Serialize,
+{
+    to_string_rooted_with_options(value, root, SerializerOptions::default())
+}
+
+pub fn to_string_rooted_with_options<T>(
+    value: &T,
+    root: &str,
+    options: SerializerOptions,
+) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_options(options);
+
+    root.serialize(&mut serializer)?;
+    serializer.output += " ";
+    value.serialize(&mut serializer)?;
+    serializer.prettify();
+
+    Ok(serializer.output)
+}
+
+// quotes and backslashes that don't start an escape get one, the parser keeps escapes as they are
+// written so text read from a file comes out unchanged
+pub(crate) fn escape(text: &str) -> Cow<'_, str> {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['"', '\\']) {
+        let (before, from) = rest.split_at(index);
+        let length = escape_length(from);
+
+        output += before;
+        if length == 0 {
+            output.push('\\');
+            output += &from[..1];
+            rest = &from[1..];
+        } else {
+            output += &from[..length];
+            rest = &from[length..];
+        }
+    }
+
+    if output.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    output += rest;
+    Cow::Owned(output)
+}
+
+// the length of the escape `text` starts with, 0 when it doesn't start one
+fn escape_length(text: &str) -> usize {
+    let mut chars = text.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some('\\'), Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => 2,
+        (Some('\\'), Some('u')) if chars.take(4).filter(char::is_ascii_hexdigit).count() == 4 => 6,
+        _ => 0,
+    }
+}
+
 pub struct Serializer {
     seq_index: usize,
     output: String,
+    options: SerializerOptions,
+    // where the last None was written
+    none_at: Option<usize>,
+    // where the key of the current map entry starts
+    entry_start: usize,
 }
 
 impl Serializer {
     pub fn new() -> Self {
+        Self::with_options(SerializerOptions::default())
+    }
+
+    pub fn with_options(options: SerializerOptions) -> Self {
         Self {
             seq_index: 0,
             output: String::new(),
+            options,
+            none_at: None,
+            entry_start: 0,
         }
     }
 
+    // writes the value of a key starting at `start`, or drops the key again when it's None
+    fn serialize_entry_value<T>(&mut self, start: usize, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.output += " ";
+
+        let value_start = self.output.len();
+        self.none_at = None;
+
+        value.serialize(&mut *self)?;
+
+        let omitted = self.options.omit_none
+            && self.none_at == Some(value_start)
+            && self.output.len() == value_start + 2;
+
+        if omitted {
+            self.output.truncate(start);
+            self.none_at = None;
+        } else {
+            self.try_newline();
+        }
+
+        Ok(())
+    }
+
     pub fn try_newline(&mut self) {
         if !self.output.ends_with('\n') && !self.output.is_empty() {
             self.output += "\n";
@@ -90,7 +238,7 @@ impl Default for Serializer {
     }
 }
 
-impl<'a> ser::Serializer for &'a mut Serializer {
+impl ser::Serializer for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
@@ -153,7 +301,7 @@ impl<'a> ser::Serializer for &'a mut Serializer {
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok> {
         self.output += "\"";
-        self.output += v;
+        self.output += &escape(v);
         self.output += "\"";
         Ok(())
     }
@@ -168,18 +316,19 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
+        self.none_at = Some(self.output.len());
         self.serialize_unit()
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
 
     fn serialize_unit(self) -> Result<Self::Ok> {
-        self.serialize_str("\"\"")
+        self.serialize_str("")
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
@@ -195,14 +344,14 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -210,7 +359,7 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         value: &T,
     ) -> Result<Self::Ok>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         self.try_newline();
         self.output += "{\n";
@@ -298,14 +447,14 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeSeq for &'a mut Serializer {
+impl ser::SerializeSeq for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let index = self.seq_index;
 
@@ -314,7 +463,8 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer {
         value.serialize(&mut **self)?;
         self.try_newline();
 
-        self.seq_index += 1;
+        // nested sequences reuse the counter
+        self.seq_index = index + 1;
 
         Ok(())
     }
@@ -325,14 +475,14 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeTuple for &'a mut Serializer {
+impl ser::SerializeTuple for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let index = self.seq_index;
 
@@ -341,7 +491,8 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
         value.serialize(&mut **self)?;
         self.try_newline();
 
-        self.seq_index += 1;
+        // nested sequences reuse the counter
+        self.seq_index = index + 1;
 
         Ok(())
     }
@@ -352,14 +503,14 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
+impl ser::SerializeTupleStruct for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let index = self.seq_index;
 
@@ -368,7 +519,8 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
         value.serialize(&mut **self)?;
         self.try_newline();
 
-        self.seq_index += 1;
+        // nested sequences reuse the counter
+        self.seq_index = index + 1;
 
         Ok(())
     }
@@ -379,14 +531,14 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
+impl ser::SerializeTupleVariant for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let index = self.seq_index;
 
@@ -395,7 +547,8 @@ impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
         value.serialize(&mut **self)?;
         self.try_newline();
 
-        self.seq_index += 1;
+        // nested sequences reuse the counter
+        self.seq_index = index + 1;
 
         Ok(())
     }
@@ -406,31 +559,30 @@ impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeMap for &'a mut Serializer {
+impl ser::SerializeMap for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
+        self.entry_start = self.output.len();
+
         self.output += "\"";
-        self.output += key.serialize(MapKeySerializer)?.as_str();
+        self.output += &escape(&key.serialize(MapKeySerializer)?);
         self.output += "\"";
 
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.output += " ";
-        value.serialize(&mut **self)?;
-        self.try_newline();
-
-        Ok(())
+        let start = self.entry_start;
+        self.serialize_entry_value(start, value)
     }
 
     fn end(self) -> Result<Self::Ok> {
@@ -439,21 +591,19 @@ impl<'a> ser::SerializeMap for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeStruct for &'a mut Serializer {
+impl ser::SerializeStruct for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        key.serialize(&mut **self)?;
-        self.output += " ";
-        value.serialize(&mut **self)?;
-        self.try_newline();
+        let start = self.output.len();
 
-        Ok(())
+        key.serialize(&mut **self)?;
+        self.serialize_entry_value(start, value)
     }
 
     fn end(self) -> Result<Self::Ok> {
@@ -462,21 +612,19 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
+impl ser::SerializeStructVariant for &mut Serializer {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        key.serialize(&mut **self)?;
-        self.output += " ";
-        value.serialize(&mut **self)?;
-        self.try_newline();
+        let start = self.output.len();
 
-        Ok(())
+        key.serialize(&mut **self)?;
+        self.serialize_entry_value(start, value)
     }
 
     fn end(self) -> Result<Self::Ok> {
@@ -678,3 +826,380 @@ impl serde::Serializer for MapKeySerializer {
         Ok(value.to_string())
     }
 }
+
+// builds the tree to_file writes, None stays apart from "" so entries can leave it out
+#[derive(Clone, Copy)]
+struct ValueSerializer {
+    omit_none: bool,
+}
+
+impl ValueSerializer {
+    fn section(self, variant: Option<&'static str>) -> SectionSerializer {
+        SectionSerializer {
+            omit_none: self.omit_none,
+            kvs: vec![],
+            key: None,
+            variant,
+        }
+    }
+}
+
+fn value_or_empty(value: Option<Value>) -> Value {
+    value.unwrap_or_else(|| Value::Value(String::new()))
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    type SerializeSeq = SectionSerializer;
+    type SerializeTuple = SectionSerializer;
+    type SerializeTupleStruct = SectionSerializer;
+    type SerializeTupleVariant = SectionSerializer;
+    type SerializeMap = SectionSerializer;
+    type SerializeStruct = SectionSerializer;
+    type SerializeStructVariant = SectionSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        self.serialize_str(if v { "1" } else { "0" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(Some(Value::Value(v.to_string())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        use ser::SerializeSeq;
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            seq.serialize_element(byte)?;
+        }
+        seq.end()
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        self.serialize_str("")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Some(Value::Section(vec![KeyValue {
+            key: variant.to_string(),
+            value: value_or_empty(value.serialize(self)?),
+        }])))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(self.section(None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(self.section(Some(variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(self.section(None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(self.section(Some(variant)))
+    }
+}
+
+struct SectionSerializer {
+    omit_none: bool,
+    kvs: Vec<KeyValue>,
+    // the key of the map entry whose value comes next
+    key: Option<String>,
+    // a variant's section is the only pair of an outer one
+    variant: Option<&'static str>,
+}
+
+impl SectionSerializer {
+    fn serializer(&self) -> ValueSerializer {
+        ValueSerializer {
+            omit_none: self.omit_none,
+        }
+    }
+
+    fn push_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(self.serializer())?;
+
+        self.kvs.push(KeyValue {
+            key: self.kvs.len().to_string(),
+            value: value_or_empty(value),
+        });
+
+        Ok(())
+    }
+
+    fn push_entry<T>(&mut self, key: String, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        match value.serialize(self.serializer())? {
+            None if self.omit_none => {}
+            value => self.kvs.push(KeyValue {
+                key,
+                value: value_or_empty(value),
+            }),
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<Value>> {
+        let section = Value::Section(self.kvs);
+
+        Ok(Some(match self.variant {
+            Some(variant) => Value::Section(vec![KeyValue {
+                key: variant.to_string(),
+                value: section,
+            }]),
+            None => section,
+        }))
+    }
+}
+
+impl ser::SerializeSeq for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().unwrap_or_default();
+        self.push_entry(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_entry(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SectionSerializer {
+    type Ok = Option<Value>;
+
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_entry(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
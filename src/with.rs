@@ -0,0 +1,415 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de,
+    ser::{self, Impossible},
+    Deserialize, Serialize,
+};
+
+use crate::{
+    canonical::natural_cmp,
+    deserializer::Deserializer,
+    error::Error,
+    kv::{KeyValue, Value},
+};
+
+// "128 -64 32" as [f32; 3], (i32, i32, i32) or Vec<u32>
+// #[serde(with = "valve_kv::with::space_separated")]
+pub mod space_separated {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: ser::Serializer,
+    {
+        let tokens = to_tokens(value).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&tokens.join(" "))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserialize_tokens(deserializer, SPACE_SEPARATED)
+    }
+}
+
+// "DOTA_ABILITY_BEHAVIOR_NO_TARGET | DOTA_ABILITY_BEHAVIOR_IMMEDIATE" as Vec<E>, HashSet<E> or BTreeSet<E>
+// of unit variants, written back sorted and without duplicates so sets are stable
+// #[serde(with = "valve_kv::with::pipe_separated")]
+pub mod pipe_separated {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: ser::Serializer,
+    {
+        let mut tokens = to_tokens(value).map_err(ser::Error::custom)?;
+
+        tokens.sort_by(|a, b| natural_cmp(a, b));
+        tokens.dedup();
+
+        serializer.serialize_str(&tokens.join(" | "))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserialize_tokens(deserializer, PIPE_SEPARATED)
+    }
+}
+
+// the elements of a sequence as they would be written in a section
+fn to_tokens<T>(value: &T) -> Result<Vec<String>, Error>
+where
+    T: Serialize,
+{
+    value.serialize(Tokens { element: false })
+}
+
+fn not_a_sequence() -> Error {
+    Error::Custom("value must be a sequence".to_string())
+}
+
+// takes a sequence whose elements are single values, None and scalars are errors
+struct Tokens {
+    // serializing an element instead of the sequence itself
+    element: bool,
+}
+
+impl Tokens {
+    fn token(self, v: String) -> Result<Vec<String>, Error> {
+        if self.element {
+            Ok(vec![v])
+        } else {
+            Err(not_a_sequence())
+        }
+    }
+
+    fn section(self) -> Error {
+        if self.element {
+            Error::ExpectedValueError
+        } else {
+            not_a_sequence()
+        }
+    }
+}
+
+impl ser::Serializer for Tokens {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    type SerializeSeq = TokenList;
+    type SerializeTuple = TokenList;
+    type SerializeTupleStruct = TokenList;
+    type SerializeTupleVariant = Impossible<Vec<String>, Error>;
+    type SerializeMap = Impossible<Vec<String>, Error>;
+    type SerializeStruct = Impossible<Vec<String>, Error>;
+    type SerializeStructVariant = Impossible<Vec<String>, Error>;
+
+    // written like the serializer writes them in a section
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        self.token(if v { "1" } else { "0" }.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        self.token(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Error> {
+        Err(self.section())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        self.token(String::new())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        self.token(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        self.token(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(self.section())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        if self.element {
+            return Err(self.section());
+        }
+
+        Ok(TokenList(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(self.section())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(self.section())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(self.section())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(self.section())
+    }
+}
+
+struct TokenList(Vec<String>);
+
+impl TokenList {
+    fn push<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.extend(value.serialize(Tokens { element: true })?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for TokenList {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeTuple for TokenList {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeTupleStruct for TokenList {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(self.0)
+    }
+}
+
+pub(crate) const SPACE_SEPARATED: &str = "$valve_kv::SpaceSeparated";
+pub(crate) const PIPE_SEPARATED: &str = "$valve_kv::PipeSeparated";
+
+pub(crate) fn split_tokens<'a>(name: &str, text: &'a str) -> Vec<&'a str> {
+    if name == PIPE_SEPARATED {
+        text.split('|')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect()
+    } else {
+        text.split_whitespace().collect()
+    }
+}
+
+// the KV deserializer splits the value itself and hands over the tokens as a sequence, so its
+// options apply to them, other formats hand over the text
+fn deserialize_tokens<'de, D, T>(deserializer: D, name: &'static str) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct TokensVisitor<T> {
+        name: &'static str,
+        marker: PhantomData<T>,
+    }
+
+    impl<'de, T> de::Visitor<'de> for TokensVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of values")
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            T::deserialize(de::value::SeqAccessDeserializer::new(seq))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            from_tokens(split_tokens(self.name, v)).map_err(de::Error::custom)
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            let text = String::deserialize(deserializer)?;
+            self.visit_str(&text)
+        }
+    }
+
+    deserializer.deserialize_newtype_struct(
+        name,
+        TokensVisitor {
+            name,
+            marker: PhantomData,
+        },
+    )
+}
+
+fn from_tokens<'de, T>(tokens: Vec<&str>) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    let section = tokens
+        .into_iter()
+        .enumerate()
+        .map(|(i, token)| KeyValue {
+            key: i.to_string(),
+            value: Value::Value(token.to_string()),
+        })
+        .collect();
+
+    T::deserialize(&mut Deserializer::from_kv(Value::Section(section)))
+}
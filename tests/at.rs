@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use valve_kv::{
+    deserializer::{from_file_at, from_file_rooted, from_str_at},
+    error::Error,
+};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Ability {
+    #[serde(rename = "AbilityCooldown")]
+    cooldown: u32,
+}
+
+const INPUT: &str = r#"
+"DOTAAbilities"
+{
+    "item_tango"
+    {
+        "AbilityCooldown" "0"
+    }
+    "item_blink"
+    {
+        "AbilityCooldown" "15"
+        "AbilityCastRange" { "value" "1200" }
+    }
+}
+"DOTAAbilities"
+{
+    "item_dagon"
+    {
+        "AbilityCooldown" "x"
+    }
+}
+"#;
+
+#[test]
+fn at_path() {
+    let res = from_str_at::<Ability>(INPUT, "DOTAAbilities/item_blink").unwrap();
+    assert_eq!(res, Ability { cooldown: 15 });
+
+    let res = from_str_at::<String>(INPUT, "/DOTAAbilities/item_blink/AbilityCastRange/value/");
+    assert_eq!(res.unwrap(), "1200");
+}
+
+#[test]
+fn at_repeated_key() {
+    let res = from_str_at::<Ability>(INPUT, "DOTAAbilities/item_dagon");
+    let err = res.unwrap_err();
+
+    assert_eq!(
+        err.path().unwrap().to_string(),
+        "DOTAAbilities/item_dagon/AbilityCooldown"
+    );
+    assert_eq!(err.location().unwrap().to_string(), "18:9");
+}
+
+#[test]
+fn at_missing() {
+    let err = from_str_at::<Ability>(INPUT, "DOTAAbilities/item_rapier").unwrap_err();
+    assert!(matches!(err, Error::PathNotFoundError(path) if path == "DOTAAbilities/item_rapier"));
+
+    assert!(from_str_at::<Ability>(INPUT, "DOTAAbilities/item_blink/AbilityCooldown/x").is_err());
+}
+
+#[test]
+fn at_file() {
+    let path = "tests/test_kvs/rooted/abilities.kv";
+
+    let res = from_file_at::<Ability>(path, "DOTAAbilities/blink").unwrap();
+    assert_eq!(res, Ability { cooldown: 15 });
+
+    let res = from_file_at::<Ability>(path, "dotaabilities/tango").unwrap();
+    assert_eq!(res, Ability { cooldown: 0 });
+}
+
+#[test]
+fn at_file_bases() {
+    let path = "tests/test_kvs/rooted/abilities.kv";
+
+    // the #base root is merged like from_file_rooted does
+    let res = from_file_at::<BTreeMap<String, Ability>>(path, "DOTAAbilities").unwrap();
+    let rooted = from_file_rooted::<BTreeMap<String, Ability>>(path, None).unwrap();
+
+    assert_eq!(res.keys().collect::<Vec<_>>(), vec!["blink", "tango"]);
+    assert_eq!(res, rooted);
+
+    let res = from_file_at::<Ability>(path, "DOTAAbilities/tango").unwrap();
+    assert_eq!(res, Ability { cooldown: 0 });
+
+    // entries of the file itself keep their position
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Flag {
+        #[serde(rename = "AbilityCooldown")]
+        cooldown: bool,
+    }
+
+    let err = from_file_at::<Flag>(path, "DOTAAbilities/blink").unwrap_err();
+    assert_eq!(
+        err.path().unwrap().to_string(),
+        "DOTAAbilities/blink/AbilityCooldown"
+    );
+    assert_eq!(err.location().unwrap().line, 7);
+}
+
+#[test]
+fn at_same_rule_for_str_and_file() {
+    let path = std::env::temp_dir().join("valve_kv_at_same_rule.kv");
+    std::fs::write(&path, INPUT).unwrap();
+    let file = path.to_str().unwrap();
+
+    // every segment matches in any case through both entry points
+    for key_path in [
+        "DOTAAbilities/item_blink",
+        "dotaabilities/ITEM_BLINK",
+        "DotaAbilities/Item_Tango",
+        "DOTAABILITIES/item_dagon",
+        "dotaabilities/item_rapier",
+    ] {
+        let from_str = from_str_at::<Ability>(INPUT, key_path).map_err(|e| e.to_string());
+        let from_file = from_file_at::<Ability>(file, key_path).map_err(|e| e.to_string());
+        assert_eq!(from_str, from_file, "{key_path}");
+    }
+
+    let res = from_str_at::<Ability>(INPUT, "dotaabilities/ITEM_BLINK").unwrap();
+    assert_eq!(res, Ability { cooldown: 15 });
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use valve_kv::{
+    canonical::{
+        canonicalize, natural_cmp, to_canonical_string, CanonicalOptions, KeyCase, LineEnding,
+    },
+    kv::Value,
+    parser::parse_input,
+    serializer::to_file_canonical,
+};
+
+#[test]
+fn numeric_aware_order() {
+    let mut keys = vec!["10", "2", "item_10", "item_9", "b", "A", "1", "02"];
+
+    keys.sort_by(|a, b| natural_cmp(a, b));
+
+    assert_eq!(
+        keys,
+        vec!["1", "02", "2", "10", "A", "b", "item_9", "item_10"]
+    );
+}
+
+#[test]
+fn canonical_value() {
+    let mut value = Value::Section(
+        parse_input(
+            r#"
+    "Values"
+    {
+        "10" "+007"
+        "2" "1.50"
+        "1" "abc"
+        "3" "1e3"
+        "4" "99999999999999999999999"
+    }
+    "Game" "b"
+    "Game" "a"
+    "#,
+        )
+        .unwrap()
+        .kvs,
+    );
+
+    canonicalize(&mut value, &CanonicalOptions::default());
+
+    let expected = Value::Section(
+        parse_input(
+            r#"
+    "Game" "b"
+    "Game" "a"
+    "Values"
+    {
+        "1" "abc"
+        "2" "1.5"
+        "3" "1e3"
+        "4" "99999999999999999999999"
+        "10" "7"
+    }
+    "#,
+        )
+        .unwrap()
+        .kvs,
+    );
+
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn canonical_options() {
+    let file = parse_input("\"Key\" \"1\"\r\n\"other\" { \"B\" \"2\" }").unwrap();
+
+    let options = CanonicalOptions {
+        key_case: KeyCase::Lower,
+        line_ending: LineEnding::CrLf,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_canonical_string(&file, &options),
+        "\"key\"\t\"1\"\r\n\r\n\"other\"\r\n{\r\n\t\"b\"\t\"2\"\r\n}\r\n"
+    );
+}
+
+#[test]
+fn canonical_multiline_values() {
+    let file = parse_input("\"lf\" \"a\nb\"\r\n\"crlf\" \"c\r\nd\"").unwrap();
+
+    let lf = to_canonical_string(&file, &CanonicalOptions::default());
+    assert_eq!(lf, "\"crlf\"\t\"c\r\nd\"\n\"lf\"\t\"a\nb\"\n");
+
+    let options = CanonicalOptions {
+        line_ending: LineEnding::CrLf,
+        ..Default::default()
+    };
+
+    let crlf = to_canonical_string(&file, &options);
+    assert_eq!(crlf, "\"crlf\"\t\"c\r\nd\"\r\n\"lf\"\t\"a\nb\"\r\n");
+    let parsed = Value::Section(parse_input(&crlf).unwrap().kvs);
+    assert_eq!(parsed.get("lf").unwrap().as_str(), Some("a\nb"));
+    assert_eq!(parsed.get("crlf").unwrap().as_str(), Some("c\r\nd"));
+}
+
+#[test]
+fn canonical_idempotent() {
+    let file = parse_input(
+        r#"
+    "z" { "10" "1.0" "9" "x" }
+    "a" "-0"
+    "M" "0.10"
+    "#,
+    )
+    .unwrap();
+
+    let options = CanonicalOptions::default();
+
+    let once = to_canonical_string(&file, &options);
+    let twice = to_canonical_string(&parse_input(&once).unwrap(), &options);
+
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn canonical_hash_map() {
+    let map: HashMap<String, u32> = (0..20).map(|i| (format!("item_{}", i), i)).collect();
+
+    let res = to_file_canonical(&map, &CanonicalOptions::default()).unwrap();
+
+    let expected: String = (0..20)
+        .map(|i| {
+            let key = format!("\"item_{}\"", i);
+            let tabs = if key.len() < 8 { "\t\t" } else { "\t" };
+
+            format!("{}{}\"{}\"\n", key, tabs, i)
+        })
+        .collect();
+
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn canonical_numbers_keep_their_value() {
+    let numbers = [
+        ("1e300", "1e300"),
+        ("-2.5E-8", "-2.5E-8"),
+        ("3.14159265358979323846", "3.14159265358979323846"),
+        (
+            "0.1000000000000000055511151231257827",
+            "0.1000000000000000055511151231257827",
+        ),
+        ("+01.2500", "1.25"),
+        ("-0.50", "-0.5"),
+        ("2.0", "2"),
+        (".5", "0.5"),
+        ("1.2.3", "1.2.3"),
+    ];
+
+    for (text, expected) in numbers {
+        let mut value = Value::Value(text.to_string());
+        canonicalize(&mut value, &CanonicalOptions::default());
+
+        assert_eq!(value, Value::Value(expected.to_string()), "{}", text);
+    }
+}
@@ -1,5 +1,16 @@
-use serde::Deserialize;
-use valve_kv::deserializer::{from_file, from_str};
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use valve_kv::{
+    deserializer::{
+        from_file, from_str, from_str_with_options, repeated, DeserializerOptions, Repeated,
+        SequenceLayout, VariantMatching,
+    },
+    error::Error,
+    kv::Value,
+    parser::parse_input,
+    serializer::to_file,
+};
 
 #[test]
 fn single_field_de() {
@@ -277,3 +288,480 @@ fn enum_de() {
         }
     );
 }
+
+#[test]
+fn numeric_order_seq_de() {
+    let input: String = (0..12)
+        .rev()
+        .map(|i| format!("\"{}\" \"{}\"\n", i, i * 10))
+        .collect();
+
+    let res = from_str::<Vec<u32>>(&input).unwrap();
+
+    assert_eq!(res, (0..12).map(|i| i * 10).collect::<Vec<_>>());
+}
+
+#[test]
+fn invalid_index_seq_de() {
+    let gap = r#""0" "a" "2" "b""#;
+    assert!(matches!(
+        from_str::<Vec<String>>(gap),
+        Err(Error::SequenceGapError(1))
+    ));
+
+    let named = r#""0" "a" "x" "b""#;
+    assert!(matches!(
+        from_str::<Vec<String>>(named),
+        Err(Error::SequenceIndexError(key)) if key == "x"
+    ));
+
+    let duplicate = r#""0" "a" "0" "b""#;
+    assert!(matches!(
+        from_str::<Vec<String>>(duplicate),
+        Err(Error::SequenceIndexError(key)) if key == "0"
+    ));
+}
+
+#[test]
+fn layout_seq_de() {
+    let options = |sequence| DeserializerOptions {
+        sequence,
+        ..Default::default()
+    };
+
+    let one_based = r#""2" "b" "1" "a""#;
+    let res: Vec<String> =
+        from_str_with_options(one_based, options(SequenceLayout::OneBased)).unwrap();
+    assert_eq!(res, vec!["a", "b"]);
+    assert!(from_str::<Vec<String>>(one_based).is_err());
+
+    let document = r#""x" "a" "y" "b" "x" "c""#;
+    let res: Vec<String> =
+        from_str_with_options(document, options(SequenceLayout::DocumentOrder)).unwrap();
+    assert_eq!(res, vec!["a", "b", "c"]);
+
+    let repeated = r#""Game" "a" "Game" "b""#;
+    let res: Vec<String> =
+        from_str_with_options(repeated, options(SequenceLayout::RepeatedKey)).unwrap();
+    assert_eq!(res, vec!["a", "b"]);
+    assert!(matches!(
+        from_str_with_options::<Vec<String>>(document, options(SequenceLayout::RepeatedKey)),
+        Err(Error::SequenceIndexError(key)) if key == "y"
+    ));
+}
+
+#[test]
+fn variant_de() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f32),
+        Line(i32, i32),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Test {
+        shapes: Vec<Shape>,
+        last: Shape,
+    }
+
+    let value = Test {
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Line(-1, 2),
+            Shape::Rect { w: 3, h: 4 },
+            Shape::Line(3, 4),
+        ],
+        last: Shape::Line(5, 6),
+    };
+
+    let res = from_str::<Test>(&to_file(&value).unwrap()).unwrap();
+
+    assert_eq!(res, value);
+}
+
+#[test]
+fn section_variant_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Ability {
+        Passive,
+        Toggle(String),
+        Active { cooldown: u32 },
+    }
+
+    let input = r#"
+    "0" { "Passive" "" }
+    "1" { "Toggle" "on" }
+    "2"
+    {
+        "Active"
+        {
+            "cooldown" "12"
+        }
+    }
+    "#;
+
+    let res = from_str::<Vec<Ability>>(input).unwrap();
+
+    assert_eq!(
+        res,
+        vec![
+            Ability::Passive,
+            Ability::Toggle("on".to_string()),
+            Ability::Active { cooldown: 12 }
+        ]
+    );
+
+    let two_keys = r#""0" { "Toggle" "on" "Passive" "" }"#;
+    assert!(matches!(
+        from_str::<Vec<Ability>>(two_keys).unwrap_err().inner(),
+        Error::ExpectedVariantError
+    ));
+}
+
+#[test]
+fn untagged_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Setting {
+        Value(String),
+        Range { min: String, max: String },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "type")]
+    enum Item {
+        Weapon { damage: String },
+        Armor { defense: String },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        a: Setting,
+        b: Setting,
+        item: Item,
+    }
+
+    let input = r#"
+    "a" "5"
+    "b" { "min" "1" "max" "2" }
+    "item" { "type" "Armor" "defense" "10" }
+    "#;
+
+    let res = from_str::<Test>(input).unwrap();
+
+    assert_eq!(
+        res,
+        Test {
+            a: Setting::Value("5".to_string()),
+            b: Setting::Range {
+                min: "1".to_string(),
+                max: "2".to_string()
+            },
+            item: Item::Armor {
+                defense: "10".to_string()
+            },
+        }
+    );
+}
+
+#[test]
+fn infer_scalars_de() {
+    let input = r#"
+    "name" "axe"
+    "level" "-3"
+    "speed" "1.5"
+    "big" "18446744073709551615"
+    "enabled" "true"
+    "inner" { "x" "1e2" }
+    "#;
+
+    let res: serde_json::Value = from_str(input).unwrap();
+    assert_eq!(res["level"], serde_json::json!("-3"));
+
+    let options = DeserializerOptions {
+        infer_scalars: true,
+        ..Default::default()
+    };
+
+    let res: serde_json::Value = from_str_with_options(input, options.clone()).unwrap();
+
+    assert_eq!(
+        res,
+        serde_json::json!({
+            "name": "axe",
+            "level": -3,
+            "speed": 1.5,
+            "big": 18446744073709551615u64,
+            "enabled": true,
+            "inner": { "x": "1e2" }
+        })
+    );
+
+    let text = r#""a" "007" "b" "1.50" "c" "-0" "d" "1.5" "e" "true" "f" "nan""#;
+
+    let res: Value = from_str_with_options(text, options.clone()).unwrap();
+    assert_eq!(res, Value::Section(parse_input(text).unwrap().kvs));
+
+    let res: HashMap<String, Value> = from_str_with_options(text, options.clone()).unwrap();
+    assert_eq!(res["a"], Value::Value("007".to_string()));
+    assert_eq!(res["b"], Value::Value("1.50".to_string()));
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Number {
+        Int(i32),
+        Float(f64),
+        Text(String),
+    }
+
+    let res: Vec<Number> = from_str_with_options(r#""0" "7" "1" "0.5" "2" "x""#, options).unwrap();
+
+    assert_eq!(
+        res,
+        vec![
+            Number::Int(7),
+            Number::Float(0.5),
+            Number::Text("x".to_string())
+        ]
+    );
+}
+
+#[test]
+fn repeated_keys_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Side {
+        plane: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Solid {
+        id: u32,
+        side: Repeated<Side>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct SearchPaths {
+        #[serde(rename = "Game", deserialize_with = "repeated")]
+        game: Vec<String>,
+        #[serde(rename = "Mod", default, deserialize_with = "repeated")]
+        mods: Vec<String>,
+        #[serde(default)]
+        solid: Repeated<Solid>,
+    }
+
+    let input = r#"
+    "Game" "dota"
+    "solid"
+    {
+        "id" "1"
+        "side" { "plane" "a" }
+        "side" { "plane" "b" }
+    }
+    "Game" "core"
+    "Game" "platform"
+    "#;
+
+    let res = from_str::<SearchPaths>(input).unwrap();
+
+    assert_eq!(res.game, vec!["dota", "core", "platform"]);
+    assert!(res.mods.is_empty());
+    assert_eq!(res.solid.0.len(), 1);
+    assert_eq!(
+        res.solid.0[0].side,
+        Repeated(vec![
+            Side {
+                plane: "a".to_string()
+            },
+            Side {
+                plane: "b".to_string()
+            }
+        ])
+    );
+}
+
+#[test]
+fn repeated_flatten_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Paths {
+        #[serde(rename = "Game")]
+        game: Repeated<String>,
+        #[serde(rename = "Side", default)]
+        side: Repeated<HashMap<String, String>>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        #[serde(flatten)]
+        paths: Paths,
+    }
+
+    let res = from_str::<Config>(r#""name" "dota" "Game" "core" "Side" { "plane" "a" }"#).unwrap();
+
+    assert_eq!(res.paths.game, Repeated(vec!["core".to_string()]));
+    assert_eq!(res.paths.side.0[0]["plane"], "a");
+
+    // serde passes flattened fields one occurrence at a time
+    let err = from_str::<Config>(r#""name" "dota" "Game" "a" "Game" "b""#).unwrap_err();
+    assert!(err.to_string().contains("duplicate field `Game`"));
+}
+
+#[test]
+fn duplicate_field_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        a: String,
+    }
+
+    let res = from_str::<Test>(r#""a" "1" "ignored" "x" "ignored" "y""#).unwrap();
+    assert_eq!(res.a, "1");
+
+    assert!(matches!(
+        from_str::<Test>(r#""a" "1" "a" "2""#).unwrap_err().inner(),
+        Error::Custom(msg) if msg == "duplicate field `a`"
+    ));
+}
+
+#[test]
+fn error_path_de() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Ability {
+        #[serde(rename = "AbilityCooldown")]
+        cooldown: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Abilities {
+        #[serde(rename = "DOTAAbilities")]
+        abilities: std::collections::HashMap<String, Ability>,
+        levels: Vec<u32>,
+    }
+
+    let input = "\"DOTAAbilities\"\n{\n\t\"my_ability\"\n\t{\n\t\t\"AbilityCooldown\" \"soon\"\n\t}\n}\n\"levels\" { \"0\" \"1\" }";
+
+    let err = from_str::<Abilities>(input).unwrap_err();
+
+    assert_eq!(
+        err.path().unwrap().to_string(),
+        "DOTAAbilities/my_ability/AbilityCooldown"
+    );
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((5, 3)));
+    assert!(matches!(err.inner(), Error::ExpectedValueError));
+    assert_eq!(
+        err.to_string(),
+        "DOTAAbilities/my_ability/AbilityCooldown (5:3): ExpectedValueError"
+    );
+
+    let input = "\"DOTAAbilities\" {}\n\"levels\" { \"0\" \"1\" \"0\" \"2\" }";
+    let err = from_str::<Abilities>(input).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "levels");
+    assert!(matches!(err.inner(), Error::SequenceIndexError(key) if key == "0"));
+
+    let input = "\"DOTAAbilities\" { \"a\" {} }\n\"levels\" {}";
+    let err = from_str::<Abilities>(input).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "DOTAAbilities/a");
+    assert_eq!(err.location().unwrap().column, 19);
+}
+
+#[test]
+fn file_error_path_de() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Test {
+        a: u32,
+    }
+
+    let err = from_file::<Test>("tests/test_kvs/base.kv").unwrap_err();
+
+    assert_eq!(err.path().unwrap().to_string(), "a");
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((3, 1)));
+}
+
+#[test]
+fn variant_matching_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Capability {
+        Passive,
+        MeleeAttack,
+        RangedAttack,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Circle(f32),
+    }
+
+    let input = r#"
+    "0" "DOTA_UNIT_CAP_MELEE_ATTACK"
+    "1" "dota_unit_cap_ranged_attack"
+    "2" "Passive"
+    "#;
+
+    assert!(from_str::<Vec<Capability>>(input).is_err());
+
+    let options = DeserializerOptions {
+        variants: VariantMatching::IgnoreCaseAndUnderscores,
+        variant_prefixes: vec!["DOTA_UNIT_CAP_".to_string()],
+        ..Default::default()
+    };
+
+    let res: Vec<Capability> = from_str_with_options(input, options.clone()).unwrap();
+    assert_eq!(
+        res,
+        vec![
+            Capability::MeleeAttack,
+            Capability::RangedAttack,
+            Capability::Passive
+        ]
+    );
+
+    let res: Vec<Shape> =
+        from_str_with_options(r#""0" { "circle" "2" }"#, options.clone()).unwrap();
+    assert_eq!(res, vec![Shape::Circle(2.0)]);
+
+    let err = from_str_with_options::<Vec<Capability>>(r#""0" "DOTA_UNIT_CAP_FLY""#, options)
+        .unwrap_err();
+    assert!(
+        matches!(err.inner(), Error::Custom(msg) if msg.starts_with("unknown variant `DOTA_UNIT_CAP_FLY`"))
+    );
+}
+
+#[test]
+fn variant_case_de() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[allow(non_camel_case_types)]
+    enum Team {
+        DOTA_TEAM_GOODGUYS,
+        DOTA_TEAM_BADGUYS,
+    }
+
+    let ignore_case = DeserializerOptions {
+        variants: VariantMatching::IgnoreCase,
+        ..Default::default()
+    };
+
+    let res: Vec<Team> =
+        from_str_with_options(r#""0" "dota_team_badguys""#, ignore_case.clone()).unwrap();
+    assert_eq!(res, vec![Team::DOTA_TEAM_BADGUYS]);
+
+    // underscores still count
+    assert!(from_str_with_options::<Vec<Team>>(r#""0" "DotaTeamGoodguys""#, ignore_case).is_err());
+
+    let prefix_only = DeserializerOptions {
+        variant_prefixes: vec!["LEGACY_".to_string()],
+        ..Default::default()
+    };
+
+    let res: Vec<Team> =
+        from_str_with_options(r#""0" "LEGACY_DOTA_TEAM_GOODGUYS""#, prefix_only.clone()).unwrap();
+    assert_eq!(res, vec![Team::DOTA_TEAM_GOODGUYS]);
+    assert!(
+        from_str_with_options::<Vec<Team>>(r#""0" "legacy_DOTA_TEAM_GOODGUYS""#, prefix_only)
+            .is_err()
+    );
+}
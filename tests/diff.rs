@@ -0,0 +1,227 @@
+use valve_kv::{
+    kv::{diff, Change, DiffPath, Patch, Value},
+    parser::parse_input,
+    serializer::to_file,
+};
+
+fn section(input: &str) -> Value {
+    Value::Section(parse_input(input).unwrap().kvs)
+}
+
+const OLD: &str = r#"
+"npc_dota_hero_axe"
+{
+    "ArmorPhysical" "0"
+    "AttackDamageMin" "27"
+    "MovementSpeed" "290"
+}
+"#;
+
+const NEW: &str = r#"
+"npc_dota_hero_axe"
+{
+    "ArmorPhysical" "2"
+    "AttackRate" "1.7"
+    "MovementSpeed" "290"
+}
+"#;
+
+#[test]
+fn reordered_keys_diff() {
+    let old = section(OLD);
+    let new = section(
+        r#"
+    "npc_dota_hero_axe"
+    {
+        "MovementSpeed" "290"
+        "AttackDamageMin" "27"
+        "ArmorPhysical" "0"
+    }
+    "#,
+    );
+
+    assert!(diff(&old, &new).is_empty());
+}
+
+#[test]
+fn changes_diff() {
+    let patch = diff(&section(OLD), &section(NEW));
+
+    assert_eq!(
+        patch.changes,
+        vec![
+            Change::Changed {
+                path: "npc_dota_hero_axe/ArmorPhysical".parse().unwrap(),
+                old: Value::Value("0".to_string()),
+                new: Value::Value("2".to_string()),
+            },
+            Change::Removed {
+                path: "npc_dota_hero_axe/AttackDamageMin".parse().unwrap(),
+                value: Value::Value("27".to_string()),
+            },
+            Change::Added {
+                path: "npc_dota_hero_axe/AttackRate".parse().unwrap(),
+                after: Some("ArmorPhysical".parse().unwrap()),
+                value: Value::Value("1.7".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn duplicate_keys_diff() {
+    let old = section(r#""Game" "dota" "Game" "core" "Game" "extra""#);
+    let new = section(r#""Game" "dota" "Game" "mod""#);
+
+    let patch = diff(&old, &new);
+
+    let paths: Vec<String> = patch.changes.iter().map(|c| c.path().to_string()).collect();
+
+    assert_eq!(paths, vec!["Game[1]", "Game[2]"]);
+
+    let mut patched = old.clone();
+    patch.apply(&mut patched).unwrap();
+
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn apply_patch() {
+    let old = section(OLD);
+    let new = section(NEW);
+
+    let mut patched = old.clone();
+    diff(&old, &new).apply(&mut patched).unwrap();
+
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn apply_insert_order() {
+    let old = section(r#""Game" "dota" "Game" "core" "Mod" "x" "Path" "y""#);
+    let new =
+        section(r#""Game" "dota" "Game" "core" "Game" "extra" "Mod" "x" "Log" "1" "Path" "y""#);
+
+    let mut patched = old.clone();
+    diff(&old, &new).apply(&mut patched).unwrap();
+
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn escaped_path_round_trip() {
+    let old = section(r#""portraits" { "models/heroes/axe/axe.vmdl" { "scale" "1" } }"#);
+    let new = section(
+        r#""portraits" { "models/heroes/axe/axe.vmdl" { "scale" "2" } "a[1]" "c" "a[1]" "d" }"#,
+    );
+
+    let patch = diff(&old, &new);
+
+    assert_eq!(
+        patch.changes[0].path().to_string(),
+        "portraits/models\\/heroes\\/axe\\/axe.vmdl/scale"
+    );
+    assert_eq!(patch.changes[0].path().0.len(), 3);
+
+    let text = to_file(&patch).unwrap();
+    let parsed = Patch::from_kv(&parse_input(&text).unwrap().kvs).unwrap();
+    assert_eq!(parsed, patch);
+
+    let json = serde_json::to_string(&patch).unwrap();
+    assert_eq!(serde_json::from_str::<Patch>(&json).unwrap(), patch);
+
+    let mut patched = old.clone();
+    Patch::from_kv(&patch.to_kv())
+        .unwrap()
+        .apply(&mut patched)
+        .unwrap();
+
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn apply_missing_path() {
+    let patch = Patch {
+        changes: vec![Change::Removed {
+            path: "a/b".parse().unwrap(),
+            value: Value::Value("c".to_string()),
+        }],
+    };
+
+    assert!(patch.apply(&mut section(OLD)).is_err());
+}
+
+#[test]
+fn patch_kv_round_trip() {
+    let patch = diff(&section(OLD), &section(NEW));
+
+    let text = to_file(&patch).unwrap();
+    let parsed = Patch::from_kv(&parse_input(&text).unwrap().kvs).unwrap();
+
+    assert_eq!(parsed, patch);
+    assert_eq!(Patch::from_kv(&patch.to_kv()).unwrap(), patch);
+}
+
+#[test]
+fn diff_path_parse() {
+    let path: DiffPath = "SearchPaths/Game[2]".parse().unwrap();
+
+    assert_eq!(path.0[1].key, "Game");
+    assert_eq!(path.0[1].occurrence, 2);
+    assert_eq!(path.to_string(), "SearchPaths/Game[2]");
+
+    let path: DiffPath = "a\\/b[1]/c[0][0]/d[x]".parse().unwrap();
+    assert_eq!(path.0[0].key, "a/b");
+    assert_eq!(path.0[0].occurrence, 1);
+    assert_eq!(path.0[1].key, "c[0]");
+    assert_eq!(path.0[2].key, "d[x]");
+    assert_eq!(path.to_string(), "a\\/b[1]/c[0][0]/d[x]");
+
+    assert!("a\\".parse::<DiffPath>().is_err());
+}
+
+#[test]
+fn empty_key_round_trip() {
+    let old = section(r#""" "1" "a" { "" "x" }"#);
+    let new = section(r#""" "2" "a" { "" "x" "" "y" }"#);
+
+    let patch = diff(&old, &new);
+
+    assert_eq!(patch.changes[0].path().to_string(), r#"\"\""#);
+    assert_eq!(patch.changes[1].path().to_string(), r#"a/\"\"[1]"#);
+
+    let json = serde_json::to_string(&patch).unwrap();
+    let parsed: Patch = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, patch);
+
+    let text = to_file(&patch).unwrap();
+    assert_eq!(
+        Patch::from_kv(&parse_input(&text).unwrap().kvs).unwrap(),
+        patch
+    );
+
+    let mut patched = old.clone();
+    parsed.apply(&mut patched).unwrap();
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn root_path_round_trip() {
+    let old = Value::Value("1".to_string());
+    let new = Value::Value("2".to_string());
+
+    let patch = diff(&old, &new);
+    assert_eq!(patch.changes[0].path().to_string(), "/");
+
+    let json = serde_json::to_string(&patch).unwrap();
+    let mut patched = old.clone();
+    serde_json::from_str::<Patch>(&json)
+        .unwrap()
+        .apply(&mut patched)
+        .unwrap();
+    assert_eq!(patched, new);
+
+    for path in ["", "a//b", "/a", "a/", "a/[1]"] {
+        assert!(path.parse::<DiffPath>().is_err(), "{}", path);
+    }
+}
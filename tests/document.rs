@@ -0,0 +1,92 @@
+use valve_kv::{document::Document, kv::Value, parser::parse_file, parser::parse_input};
+
+const INPUT: &str = r#"
+"items_game"
+{
+    "prefabs"
+    {
+        "weapon_base"
+        {
+            "prefab" "base"
+            "name" "weapon"
+        }
+        "weapon_ak47"
+        {
+            "prefab" "weapon_base"
+            "name" "ak47"
+        }
+    }
+    "items"
+    {
+        "7" { "name" "weapon_ak47" }
+    }
+}
+"#;
+
+#[test]
+fn document_round_trip() {
+    let kvs = parse_input(INPUT).unwrap().kvs;
+
+    let document = Document::parse(INPUT).unwrap();
+
+    assert_eq!(document.to_kvs(), kvs);
+    assert_eq!(Document::from_kvs(&kvs).to_kvs(), kvs);
+    assert_eq!(document.len(), 11);
+}
+
+#[test]
+fn document_read_api() {
+    let document = Document::parse(INPUT).unwrap();
+    let value = Value::Section(parse_input(INPUT).unwrap().kvs);
+
+    let node = document
+        .lookup("items_game/prefabs/weapon_ak47/name")
+        .unwrap();
+
+    assert_eq!(node.key(), "name");
+    assert_eq!(node.as_str(), Some("ak47"));
+    assert_eq!(
+        value
+            .lookup("items_game/prefabs/weapon_ak47/name")
+            .and_then(Value::as_str),
+        Some("ak47")
+    );
+
+    let prefabs = document.lookup("items_game/prefabs").unwrap();
+
+    assert!(prefabs.is_section());
+    assert_eq!(
+        prefabs.children().map(|n| n.key()).collect::<Vec<_>>(),
+        vec!["weapon_base", "weapon_ak47"]
+    );
+    assert_eq!(
+        prefabs.to_value(),
+        value.lookup("items_game/prefabs").unwrap().clone()
+    );
+
+    assert!(document.lookup("items_game/missing").is_none());
+    assert!(document.get("unknown_key").is_none());
+}
+
+#[test]
+fn document_duplicate_keys() {
+    let document = Document::parse(r#""Game" "dota" "Game" "core" "Other" "x""#).unwrap();
+
+    let games: Vec<_> = document
+        .get_all("Game")
+        .filter_map(|n| n.as_str())
+        .collect();
+
+    assert_eq!(games, vec!["dota", "core"]);
+}
+
+#[test]
+fn document_file() {
+    let document = Document::from_file("./tests/test_kvs/base.kv").unwrap();
+
+    assert_eq!(
+        document.to_kvs(),
+        parse_file("./tests/test_kvs/base.kv").unwrap()
+    );
+    assert_eq!(document.imports, vec!["nested/nested.kv".to_string()]);
+}
@@ -0,0 +1,185 @@
+use serde::Serialize;
+use valve_kv::{
+    formatter::{format_file, format_kvs, format_value, BracePlacement, FormatOptions},
+    kv::{KeyValue, Value},
+    parser::parse_input,
+    serializer::{to_file, to_file_formatted},
+};
+
+const INPUT: &str = r#"
+"Version" "1"
+"axe_berserkers_call"
+{
+    "BaseClass" "ability_lua"
+    "AbilityCooldown" "17"
+    "AbilityValues"
+    {
+        "radius" "300"
+    }
+}
+"axe_culling_blade" { "AbilityType" "DOTA_ABILITY_TYPE_ULTIMATE" }
+"#;
+
+#[test]
+fn valve_style_format() {
+    let kvs = parse_input(INPUT).unwrap().kvs;
+
+    let expected = "\"Version\"\t\"1\"\n\
+\n\
+\"axe_berserkers_call\"\n\
+{\n\
+\t\"BaseClass\"\t\t\t\"ability_lua\"\n\
+\t\"AbilityCooldown\"\t\"17\"\n\
+\t\"AbilityValues\"\n\
+\t{\n\
+\t\t\"radius\"\t\"300\"\n\
+\t}\n\
+}\n\
+\n\
+\"axe_culling_blade\"\n\
+{\n\
+\t\"AbilityType\"\t\"DOTA_ABILITY_TYPE_ULTIMATE\"\n\
+}\n";
+
+    assert_eq!(format_kvs(&kvs, &FormatOptions::default()), expected);
+}
+
+#[test]
+fn custom_format() {
+    let kvs = parse_input(INPUT).unwrap().kvs;
+
+    let options = FormatOptions {
+        indent: "  ".to_string(),
+        align_with_tabs: false,
+        blank_lines: false,
+        braces: BracePlacement::SameLine,
+        ..Default::default()
+    };
+
+    let expected = r#""Version" "1"
+"axe_berserkers_call" {
+  "BaseClass"       "ability_lua"
+  "AbilityCooldown" "17"
+  "AbilityValues" {
+    "radius" "300"
+  }
+}
+"axe_culling_blade" {
+  "AbilityType" "DOTA_ABILITY_TYPE_ULTIMATE"
+}
+"#;
+
+    assert_eq!(format_kvs(&kvs, &options), expected);
+}
+
+#[test]
+fn format_reparses() {
+    let file = parse_input(&format!("#base \"base.kv\"\n{}", INPUT)).unwrap();
+
+    let formatted = format_file(&file, &FormatOptions::default());
+
+    assert!(formatted.starts_with("#base \"base.kv\"\n\n\"Version\""));
+    assert_eq!(parse_input(&formatted).unwrap(), file);
+}
+
+#[test]
+fn format_value_and_serialized() {
+    #[derive(Serialize)]
+    struct Test {
+        a: u32,
+        long_name: &'static str,
+    }
+
+    let options = FormatOptions::default();
+
+    assert_eq!(
+        to_file_formatted(
+            &Test {
+                a: 1,
+                long_name: "x"
+            },
+            &options
+        )
+        .unwrap(),
+        "\"a\"\t\t\t\"1\"\n\"long_name\"\t\"x\"\n"
+    );
+    assert_eq!(
+        format_value(&Value::Value("x".to_string()), &options),
+        "\"x\"\n"
+    );
+}
+
+#[test]
+fn format_escapes() {
+    let kvs = vec![
+        KeyValue {
+            key: "say \"hi\"".to_string(),
+            value: Value::Value("C:\\dota".to_string()),
+        },
+        KeyValue {
+            key: "read".to_string(),
+            value: Value::Value("already \\\"escaped\\\"\\n".to_string()),
+        },
+    ];
+
+    let formatted = format_kvs(&kvs, &FormatOptions::default());
+
+    assert_eq!(
+        formatted,
+        "\"say \\\"hi\\\"\"\t\"C:\\\\dota\"\n\"read\"\t\t\t\"already \\\"escaped\\\"\\n\"\n"
+    );
+
+    // text read from a file keeps its escapes
+    let file = parse_input(&formatted).unwrap();
+    assert_eq!(format_kvs(&file.kvs, &FormatOptions::default()), formatted);
+}
+
+#[test]
+fn format_aligns_with_space_indent() {
+    let kvs = parse_input(r#""Unit" { "a" "1" "long_key" "2" }"#)
+        .unwrap()
+        .kvs;
+
+    let options = FormatOptions {
+        indent: "  ".to_string(),
+        ..Default::default()
+    };
+
+    // the tabs line up at column 16, counted from the start of the line
+    assert_eq!(
+        format_kvs(&kvs, &options),
+        "\"Unit\"\n{\n  \"a\"\t\t\t\"1\"\n  \"long_key\"\t\"2\"\n}\n"
+    );
+}
+
+#[test]
+fn format_serialized_tree() {
+    #[derive(Serialize)]
+    enum Kind {
+        Melee,
+        Ranged { range: u32 },
+    }
+
+    #[derive(Serialize)]
+    struct Unit {
+        name: &'static str,
+        kinds: Vec<Kind>,
+        tags: (u8, Option<u8>),
+        skin: Option<u8>,
+    }
+
+    let unit = Unit {
+        name: "say \"hi\"",
+        kinds: vec![Kind::Melee, Kind::Ranged { range: 600 }],
+        tags: (1, None),
+        skin: None,
+    };
+
+    let options = FormatOptions::default();
+    let formatted = to_file_formatted(&unit, &options).unwrap();
+
+    // the same tree to_file writes
+    let written = parse_input(&to_file(&unit).unwrap()).unwrap().kvs;
+    assert_eq!(formatted, format_kvs(&written, &options));
+    assert!(formatted.starts_with("\"name\"\t\"say \\\"hi\\\"\"\n"));
+}
@@ -0,0 +1,151 @@
+use valve_kv::{
+    kv::Value,
+    parser::parse_input,
+    schema::{infer_dir, InferOptions, Schema, SchemaInferrer, SchemaType},
+    serializer::to_file,
+};
+
+fn unit_schema(schema: &Schema) -> &Schema {
+    schema
+        .child("DOTAUnits")
+        .and_then(|units| units.other.as_deref())
+        .unwrap()
+}
+
+fn infer_units() -> Schema {
+    infer_dir("./tests/test_kvs/units").unwrap()
+}
+
+#[test]
+fn infer_value_shapes() {
+    let schema = infer_units();
+    let unit = unit_schema(&schema);
+
+    assert_eq!(
+        unit.child("IsAncient").unwrap().value_type,
+        SchemaType::Bool
+    );
+    assert_eq!(
+        unit.child("AttackRate").unwrap().value_type,
+        SchemaType::Float
+    );
+
+    let speed = unit.child("MovementSpeed").unwrap();
+
+    assert_eq!(speed.value_type, SchemaType::Int);
+    assert_eq!((speed.min, speed.max), (Some(290.0), Some(310.0)));
+
+    let capabilities = unit.child("AttackCapabilities").unwrap();
+
+    assert_eq!(capabilities.value_type, SchemaType::Enum);
+    assert_eq!(
+        capabilities.values,
+        vec!["DOTA_UNIT_CAP_MELEE_ATTACK", "DOTA_UNIT_CAP_RANGED_ATTACK"]
+    );
+    assert_eq!(
+        unit.child("ProjectileModel").unwrap().value_type,
+        SchemaType::String
+    );
+}
+
+#[test]
+fn infer_frequency() {
+    let schema = infer_units();
+    let unit = unit_schema(&schema);
+
+    assert!(schema.child("DOTAUnits").unwrap().required);
+    assert!(unit.child("MovementSpeed").unwrap().required);
+    assert!(!unit.child("ProjectileModel").unwrap().required);
+    assert!(unit.child("Ability").unwrap().repeated);
+    assert!(!unit.child("AttackRate").unwrap().repeated);
+}
+
+#[test]
+fn infer_map_threshold() {
+    let mut inferrer = SchemaInferrer::with_options(InferOptions {
+        map_threshold: 2,
+        ..Default::default()
+    });
+
+    inferrer
+        .add_str(r#""items" { "1" { "name" "a" } "2" { "name" "b" } "3" { "name" "c" } }"#)
+        .unwrap();
+
+    let schema = inferrer.infer();
+    let items = schema.child("items").unwrap();
+
+    assert!(items.children.is_empty());
+    assert_eq!(
+        items
+            .other
+            .as_deref()
+            .unwrap()
+            .child("name")
+            .unwrap()
+            .value_type,
+        SchemaType::String
+    );
+}
+
+#[test]
+fn infer_stats() {
+    let mut inferrer = SchemaInferrer::new();
+
+    inferrer.add_str(r#""a" "1" "b" "x""#).unwrap();
+    inferrer.add_str(r#""a" "2""#).unwrap();
+
+    let stats = inferrer.stats();
+
+    assert_eq!(stats.frequency("a"), 1.0);
+    assert_eq!(stats.frequency("b"), 0.5);
+    assert_eq!(stats.child("a").unwrap().occurrences, 2);
+    assert_eq!(stats.child("a").unwrap().max, Some(2.0));
+}
+
+#[test]
+fn inferred_schema_reads_back() {
+    let schema = infer_dir("./tests/test_kvs/units").unwrap();
+
+    let text = to_file(&Value::Section(schema.to_kvs())).unwrap();
+    let parsed: Schema = text.parse().unwrap();
+
+    assert_eq!(parsed, schema);
+
+    let sample = Value::Section(
+        parse_input(&std::fs::read_to_string("./tests/test_kvs/units/lina.kv").unwrap())
+            .unwrap()
+            .kvs,
+    );
+
+    assert_eq!(schema.validate(&sample), vec![]);
+}
+
+#[test]
+fn infer_dir_skips_other_files() {
+    let dir = std::env::temp_dir().join(format!("valve_kv_infer_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::fs::write(
+        dir.join("axe.txt"),
+        r#""npc_dota_hero_axe" { "ArmorPhysical" "2" }"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("README.md"), "# Heroes\n").unwrap();
+    std::fs::write(dir.join(".DS_Store"), [0, 0, 0, 1, 0xff, 0xfe]).unwrap();
+
+    let mut inferrer = SchemaInferrer::new();
+    inferrer.add_dir(&dir.to_string_lossy()).unwrap();
+
+    let mut skipped: Vec<_> = inferrer
+        .skipped()
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    skipped.sort();
+
+    assert_eq!(skipped, vec![".DS_Store", "README.md"]);
+    assert_eq!(inferrer.stats().sections, 1);
+    assert!(inferrer.infer().child("npc_dota_hero_axe").is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
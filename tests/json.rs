@@ -0,0 +1,85 @@
+use serde_json::json;
+use valve_kv::{
+    json::{from_json, to_json, JsonMode},
+    kv::Value,
+    parser::parse_input,
+};
+
+const INPUT: &str = r#"
+"GameInfo"
+{
+    "game" "dota"
+    "SearchPaths"
+    {
+        "Game" "dota"
+        "Mod" "dota"
+        "Game" "core"
+    }
+}
+"#;
+
+fn value() -> Value {
+    Value::Section(parse_input(INPUT).unwrap().kvs)
+}
+
+#[test]
+fn object_mode() {
+    assert_eq!(
+        to_json(&value(), JsonMode::Object),
+        json!({
+            "GameInfo": {
+                "game": "dota",
+                "SearchPaths": { "Game": "core", "Mod": "dota" }
+            }
+        })
+    );
+}
+
+#[test]
+fn lossless_mode() {
+    let json = to_json(&value(), JsonMode::Lossless);
+
+    assert_eq!(
+        json,
+        json!([[
+            "GameInfo",
+            [
+                ["game", "dota"],
+                [
+                    "SearchPaths",
+                    [["Game", "dota"], ["Mod", "dota"], ["Game", "core"]]
+                ]
+            ]
+        ]])
+    );
+
+    assert_eq!(from_json(&json, JsonMode::Lossless).unwrap(), value());
+}
+
+#[test]
+fn object_mode_scalars() {
+    let json = json!({ "a": 1.5, "b": true, "c": null, "d": ["x", "y"] });
+
+    let expected = parse_input(
+        r#"
+    "a" "1.5"
+    "b" "1"
+    "c" ""
+    "d" { "0" "x" "1" "y" }
+    "#,
+    )
+    .unwrap()
+    .kvs;
+
+    assert_eq!(
+        from_json(&json, JsonMode::Object).unwrap(),
+        Value::Section(expected)
+    );
+}
+
+#[test]
+fn lossless_mode_errors() {
+    assert!(from_json(&json!({ "a": "b" }), JsonMode::Lossless).is_err());
+    assert!(from_json(&json!([["a"]]), JsonMode::Lossless).is_err());
+    assert!(from_json(&json!([[1, "b"]]), JsonMode::Lossless).is_err());
+}
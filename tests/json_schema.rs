@@ -0,0 +1,269 @@
+use std::{
+    net::Ipv4Addr,
+    num::{NonZeroU16, NonZeroU32},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use valve_kv::{
+    kv::Value,
+    parser::parse_input,
+    schema::{json_schema_for, Schema, SchemaType},
+    serializer::to_file,
+};
+
+#[test]
+fn kv_schema_to_json_schema() {
+    let schema: Schema = r#"
+    "type" "section"
+    "closed" "1"
+    "children"
+    {
+        "Version" { "type" "int" "required" "1" }
+        "Name" { "type" "string" }
+        "IsHidden" { "type" "bool" }
+        "Kind" { "type" "enum" "values" { "0" "melee" "1" "ranged" } }
+    }
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        schema.to_json_schema(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "Version": { "type": "string", "pattern": "^[+-]?[0-9]+$" },
+                "Name": { "type": "string" },
+                "IsHidden": { "type": "string", "enum": ["0", "1"] },
+                "Kind": { "type": "string", "enum": ["melee", "ranged"] },
+            },
+            "required": ["Version"],
+            "additionalProperties": false,
+        })
+    );
+}
+
+#[test]
+fn other_to_additional_properties() {
+    let schema: Schema = r#"
+    "type" "section"
+    "other" { "type" "float" }
+    "#
+    .parse()
+    .unwrap();
+
+    let json = schema.to_json_schema();
+
+    assert_eq!(json["additionalProperties"]["type"], json!("string"));
+    assert!(json.get("properties").is_none());
+}
+
+#[test]
+fn schema_from_type() {
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    enum Kind {
+        Melee,
+        Ranged,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Ability {
+        cooldown: f32,
+        level: u8,
+        kind: Kind,
+        name: Option<String>,
+        values: Vec<i32>,
+        children: Vec<Ability>,
+    }
+
+    let schema = Schema::from_type::<Ability>().unwrap();
+
+    assert_eq!(schema.value_type, SchemaType::Section);
+    assert_eq!(
+        schema.child("cooldown").unwrap().value_type,
+        SchemaType::Float
+    );
+    assert!(schema.child("cooldown").unwrap().required);
+
+    let level = schema.child("level").unwrap();
+
+    assert_eq!((level.min, level.max), (Some(0.0), Some(255.0)));
+    assert_eq!(
+        schema.child("kind").unwrap().values,
+        vec!["Melee", "Ranged"]
+    );
+    assert!(!schema.child("name").unwrap().required);
+
+    let values = schema.child("values").unwrap();
+
+    assert_eq!(values.value_type, SchemaType::Section);
+    assert_eq!(values.other.as_ref().unwrap().value_type, SchemaType::Int);
+}
+
+#[test]
+fn json_schema_from_type() {
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Unit {
+        name: String,
+        position: (f32, f32),
+    }
+
+    let json = json_schema_for::<Unit>().unwrap();
+
+    assert_eq!(json["required"], json!(["name", "position"]));
+    assert_eq!(
+        json["properties"]["position"]["required"],
+        json!(["0", "1"])
+    );
+}
+
+#[test]
+fn schema_from_data_variants() {
+    #[derive(Serialize, Deserialize)]
+    enum Shape {
+        Point,
+        Circle(f32),
+        Rect(f32, f32),
+        Polygon { sides: u8 },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Area {
+        shape: Shape,
+        outline: Shape,
+        radius: Option<u32>,
+    }
+
+    let schema = Schema::from_type::<Area>().unwrap();
+    let shape = schema.child("shape").unwrap();
+
+    assert_eq!(shape.value_type, SchemaType::Enum);
+    assert_eq!(shape.values, vec!["Point"]);
+
+    let variants: Vec<&str> = shape
+        .variants
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(variants, vec!["Circle", "Rect", "Polygon"]);
+
+    let json = json_schema_for::<Area>().unwrap();
+
+    assert_eq!(
+        json["properties"]["shape"]["oneOf"][1],
+        json!({
+            "type": "object",
+            "properties": { "Circle": { "type": "string", "pattern": r"^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$" } },
+            "required": ["Circle"],
+            "additionalProperties": false,
+        })
+    );
+    assert_eq!(
+        json["properties"]["radius"]["anyOf"][1],
+        json!({ "const": "" })
+    );
+
+    // the crate's own output passes the traced schema
+    let area = Area {
+        shape: Shape::Circle(1.5),
+        outline: Shape::Polygon { sides: 6 },
+        radius: None,
+    };
+
+    let text = to_file(&area).unwrap();
+    let value = Value::Section(parse_input(&text).unwrap().kvs);
+
+    assert_eq!(schema.validate(&value), vec![]);
+
+    let text = to_file(&Area {
+        shape: Shape::Point,
+        outline: Shape::Rect(1.0, 2.0),
+        radius: Some(3),
+    })
+    .unwrap();
+    let value = Value::Section(parse_input(&text).unwrap().kvs);
+
+    assert_eq!(schema.validate(&value), vec![]);
+
+    let text: Schema = to_file(&Value::Section(schema.to_kvs()))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(text, schema);
+}
+
+#[test]
+fn schema_from_validating_types() {
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Server {
+        name: String,
+        #[serde(default)]
+        players: u32,
+        address: Ipv4Addr,
+        port: NonZeroU16,
+        backups: Vec<NonZeroU32>,
+    }
+
+    let json = json_schema_for::<NonZeroU32>().unwrap();
+    assert!(json["pattern"].is_string());
+
+    let schema = Schema::from_type::<Server>().unwrap();
+
+    assert_eq!(
+        schema.child("address").unwrap().value_type,
+        SchemaType::String
+    );
+    assert_eq!(schema.child("port").unwrap().value_type, SchemaType::Int);
+    assert_eq!(schema.child("name").unwrap().value_type, SchemaType::String);
+    assert_eq!(
+        schema
+            .child("backups")
+            .unwrap()
+            .other
+            .as_ref()
+            .unwrap()
+            .value_type,
+        SchemaType::Int
+    );
+
+    let json = json_schema_for::<Server>().unwrap();
+    assert_eq!(
+        json["required"],
+        json!(["name", "address", "port", "backups"])
+    );
+    assert!(json["properties"]["players"]["pattern"].is_string());
+}
+
+#[test]
+fn schema_from_defaulted_struct() {
+    #[allow(dead_code)]
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct Settings {
+        volume: u8,
+        inner: Inner,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, Default)]
+    struct Inner {
+        a: String,
+        #[serde(default)]
+        b: String,
+    }
+
+    let schema = Schema::from_type::<Settings>().unwrap();
+
+    assert!(!schema.child("volume").unwrap().required);
+
+    let inner = schema.child("inner").unwrap();
+    assert!(!inner.required);
+    assert!(inner.child("a").unwrap().required);
+    assert!(!inner.child("b").unwrap().required);
+}
@@ -0,0 +1,171 @@
+use valve_kv::{
+    error::Error,
+    lint::{Diagnostic, LintConfig, LintContext, LintRule, Linter},
+    parser::SpannedValue,
+};
+
+fn rules(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.rule).collect()
+}
+
+#[test]
+fn lint_builtin_rules() {
+    let input = r#"
+    "Game"
+    {
+        "Name" "Dota "
+        "name" "dota"
+        "Speed" "1,5"
+        "Count" "1,000"
+        "Empty" {}
+        "Tag" "a"
+        "Tag" "b"
+    }
+    "#;
+
+    let diagnostics = Linter::new().lint_str(input).unwrap();
+
+    assert_eq!(
+        rules(&diagnostics),
+        vec![
+            "trailing-whitespace",
+            "key-case",
+            "suspicious-number",
+            "suspicious-number",
+            "empty-section",
+            "duplicate-keys"
+        ]
+    );
+
+    assert_eq!(diagnostics[0].path.to_string(), "Game/Name");
+    assert_eq!(diagnostics[1].span.start.line, 5);
+    assert_eq!(diagnostics[1].span.start.column, 9);
+    assert_eq!(
+        diagnostics[4].to_string(),
+        "8:9: Game/Empty: section \"Empty\" is empty [empty-section]"
+    );
+}
+
+#[test]
+fn lint_fixes() {
+    let input = "\"Game\"\n{\n\t\"Name\" \"Dota \"\n\t\"NAME\" \"x\"\n\t\"Speed\" \"-1,25\"\n\t\"Count\" \"1,000\"\n\t\"Empty\"\n\t{\n\t}\n}\n";
+
+    let (output, remaining) = Linter::new().fix_str(input).unwrap();
+
+    assert_eq!(
+        output,
+        "\"Game\"\n{\n\t\"Name\" \"Dota\"\n\t\"NAME\" \"x\"\n\t\"Speed\" \"-1.25\"\n\t\"Count\" \"1,000\"\n\t\"Empty\"\n\t{\n\t}\n}\n"
+    );
+    // renaming "NAME" would make it a duplicate of "Name"
+    assert_eq!(
+        rules(&remaining),
+        vec!["key-case", "suspicious-number", "empty-section"]
+    );
+}
+
+#[test]
+fn lint_fix_key_case_sections() {
+    let input = "\"Unit\"\n{\n\t\"Model\"\n\t{\n\t\t\"Scale\" \"1\"\n\t}\n\t\"model\"\n\t{\n\t\t\"Skin\" \"2\"\n\t}\n\t\"MODEL\"\n\t{\n\t\t\"scale\" \"3\"\n\t}\n}\n";
+
+    let linter = Linter::new();
+    let (output, remaining) = linter.fix_str(input).unwrap();
+
+    // "model" is merged into "Model", "MODEL" would set "Scale" twice
+    assert_eq!(
+        output,
+        "\"Unit\"\n{\n\t\"Model\"\n\t{\n\t\t\"Scale\" \"1\"\n\t\t\"Skin\" \"2\"\n\t}\n\t\"MODEL\"\n\t{\n\t\t\"scale\" \"3\"\n\t}\n}\n"
+    );
+    assert_eq!(rules(&remaining), vec!["key-case"]);
+    assert_eq!(rules(&linter.lint_str(&output).unwrap()), vec!["key-case"]);
+
+    let input = r#""Unit" { "Model" { "Scale" "1" } "model" { "Skin" "2" } }"#;
+    let (output, remaining) = linter.fix_str(input).unwrap();
+
+    assert_eq!(output, r#""Unit" { "Model" { "Scale" "1" "Skin" "2" } }"#);
+    assert!(remaining.is_empty());
+    assert!(linter.lint_str(&output).unwrap().is_empty());
+}
+
+#[test]
+fn lint_config() {
+    let config: LintConfig = r#"
+    "duplicate-keys" "0"
+    "key-case" "1"
+    "#
+    .parse()
+    .unwrap();
+
+    let input = r#""a" "1" "a" "2" "A" "3""#;
+
+    let diagnostics = Linter::with_config(config).lint_str(input).unwrap();
+    assert_eq!(rules(&diagnostics), vec!["key-case"]);
+
+    let mut linter = Linter::new();
+    linter.config_mut().disable("key-case");
+
+    let diagnostics = linter.lint_str(input).unwrap();
+    assert_eq!(rules(&diagnostics), vec!["duplicate-keys"]);
+
+    assert!(matches!(
+        r#""key-case" "yes""#.parse::<LintConfig>(),
+        Err(Error::InvalidLintConfigError(_))
+    ));
+}
+
+#[test]
+fn lint_file_bases() {
+    let diagnostics = Linter::new()
+        .lint_file("tests/test_kvs/lint/unit.kv")
+        .unwrap();
+
+    assert_eq!(
+        rules(&diagnostics),
+        vec!["unreachable-base", "suspicious-number"]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "base file \"missing.kv\" does not exist"
+    );
+
+    // without a path the bases can't be resolved
+    let diagnostics = Linter::new().lint_str("#base \"missing.kv\"").unwrap();
+    assert!(diagnostics.is_empty());
+}
+
+struct NoTodo;
+
+impl LintRule for NoTodo {
+    fn name(&self) -> &'static str {
+        "no-todo"
+    }
+
+    fn check(&self, context: &LintContext, diagnostics: &mut Vec<Diagnostic>) {
+        for kv in &context.file.kvs {
+            if let SpannedValue::Value(v) = &kv.value {
+                if v.contains("TODO") {
+                    diagnostics.push(Diagnostic {
+                        rule: self.name(),
+                        message: "unfinished value".to_string(),
+                        path: kv.key.as_str().into(),
+                        span: kv.value_span,
+                        fix: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn lint_custom_rule() {
+    let mut linter = Linter::new();
+    linter.add_rule(NoTodo);
+
+    let diagnostics = linter.lint_str(r#""a" "TODO" "b" "done""#).unwrap();
+
+    assert_eq!(rules(&diagnostics), vec!["no-todo"]);
+    assert_eq!(diagnostics[0].span.start.column, 5);
+
+    linter.config_mut().disable("no-todo");
+    assert!(linter.lint_str(r#""a" "TODO""#).unwrap().is_empty());
+}
@@ -0,0 +1,110 @@
+use valve_kv::{
+    kv::{merge, merge_with_report, KeyPath, KeyValue, MergeStrategy},
+    parser::parse_input,
+};
+
+fn kvs(input: &str) -> Vec<KeyValue> {
+    parse_input(input).unwrap().kvs
+}
+
+const BASE: &str = r#"
+"npc_dota_hero_axe"
+{
+    "ArmorPhysical" "0"
+    "AttackDamageMin" "27"
+    "Ability1" "axe_berserkers_call"
+}
+"#;
+
+const OVERLAY: &str = r#"
+"npc_dota_hero_axe"
+{
+    "ArmorPhysical" "2"
+    "MovementSpeed" "310"
+}
+"#;
+
+#[test]
+fn recursive_merge() {
+    let res = merge(kvs(BASE), kvs(OVERLAY), MergeStrategy::Recursive);
+
+    let expected = kvs(r#"
+    "npc_dota_hero_axe"
+    {
+        "ArmorPhysical" "2"
+        "AttackDamageMin" "27"
+        "Ability1" "axe_berserkers_call"
+        "MovementSpeed" "310"
+    }
+    "#);
+
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn replace_merge() {
+    let res = merge(kvs(BASE), kvs(OVERLAY), MergeStrategy::Replace);
+
+    assert_eq!(res, kvs(OVERLAY));
+}
+
+#[test]
+fn append_merge() {
+    let res = merge(kvs(BASE), kvs(OVERLAY), MergeStrategy::Append);
+
+    let mut expected = kvs(BASE);
+    expected.append(&mut kvs(OVERLAY));
+
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn keep_base_merge() {
+    let res = merge(kvs(BASE), kvs(OVERLAY), MergeStrategy::KeepBase);
+
+    let expected = kvs(r#"
+    "npc_dota_hero_axe"
+    {
+        "ArmorPhysical" "0"
+        "AttackDamageMin" "27"
+        "Ability1" "axe_berserkers_call"
+        "MovementSpeed" "310"
+    }
+    "#);
+
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn duplicate_keys_merge() {
+    let base = kvs(r#"
+    "Game" "dota"
+    "Game" "core"
+    "#);
+
+    let overlay = kvs(r#"
+    "Game" "dota"
+    "Game" "mod"
+    "Game" "extra"
+    "#);
+
+    let (res, report) = merge_with_report(base, overlay, MergeStrategy::Recursive);
+
+    assert_eq!(res, kvs(r#""Game" "dota" "Game" "mod" "Game" "extra""#));
+    assert_eq!(report, vec![KeyPath::from("Game")]);
+}
+
+#[test]
+fn merge_report() {
+    let (_, report) = merge_with_report(kvs(BASE), kvs(OVERLAY), MergeStrategy::Recursive);
+
+    assert_eq!(
+        report,
+        vec![KeyPath::from("npc_dota_hero_axe/ArmorPhysical")]
+    );
+    assert_eq!(report[0].to_string(), "npc_dota_hero_axe/ArmorPhysical");
+
+    let (_, report) = merge_with_report(kvs(BASE), kvs(OVERLAY), MergeStrategy::KeepBase);
+
+    assert!(report.is_empty());
+}
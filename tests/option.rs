@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use valve_kv::{
+    deserializer::{from_str, from_str_with_options, DeserializerOptions},
+    serializer::{to_file, to_file_with_options, SerializerOptions},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+struct Inner {
+    a: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+struct Test {
+    name: Option<String>,
+    inner: Option<Inner>,
+    count: Option<u32>,
+}
+
+#[test]
+fn none_ser() {
+    let value = Test {
+        name: None,
+        inner: Some(Inner { a: "x".to_string() }),
+        count: None,
+    };
+
+    assert_eq!(
+        to_file(&value).unwrap(),
+        "\"name\" \"\"\n\"inner\" \n{\n  \"a\" \"x\"\n}\n\"count\" \"\""
+    );
+
+    let omit = SerializerOptions { omit_none: true };
+
+    assert_eq!(
+        to_file_with_options(&value, omit.clone()).unwrap(),
+        "\"inner\" \n{\n  \"a\" \"x\"\n}"
+    );
+    assert_eq!(to_file_with_options(&Test::default(), omit).unwrap(), "");
+}
+
+#[test]
+fn none_in_maps_ser() {
+    let mut map = BTreeMap::new();
+    map.insert("a", Some(""));
+    map.insert("b", None);
+    map.insert("c", Some("c"));
+
+    let omit = SerializerOptions { omit_none: true };
+
+    assert_eq!(
+        to_file_with_options(&map, omit.clone()).unwrap(),
+        "\"a\" \"\"\n\"c\" \"c\""
+    );
+
+    // sequences keep their indices
+    let seq = vec![None, Some(1)];
+    assert_eq!(
+        to_file_with_options(&seq, omit).unwrap(),
+        "\"0\" \"\"\n\"1\" \"1\""
+    );
+}
+
+#[test]
+fn option_de() {
+    let input = r#"
+    "name" ""
+    "inner" { "a" "x" }
+    "#;
+
+    let res = from_str::<Test>(input).unwrap();
+
+    assert_eq!(
+        res,
+        Test {
+            name: None,
+            inner: Some(Inner { a: "x".to_string() }),
+            count: None,
+        }
+    );
+
+    let options = DeserializerOptions {
+        empty_as_none: false,
+        ..Default::default()
+    };
+
+    let res: Test = from_str_with_options(input, options).unwrap();
+    assert_eq!(res.name, Some(String::new()));
+    assert_eq!(res.count, None);
+}
+
+#[test]
+fn option_round_trip() {
+    let value = Test {
+        name: Some(String::new()),
+        inner: None,
+        count: Some(3),
+    };
+
+    let output = to_file_with_options(&value, SerializerOptions { omit_none: true }).unwrap();
+
+    let options = DeserializerOptions {
+        empty_as_none: false,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        from_str_with_options::<Test>(&output, options).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn unit_ser() {
+    #[derive(Serialize)]
+    struct Unit;
+
+    #[derive(Serialize)]
+    struct Test {
+        unit: (),
+        unit_struct: Unit,
+    }
+
+    assert_eq!(
+        to_file(&Test {
+            unit: (),
+            unit_struct: Unit
+        })
+        .unwrap(),
+        "\"unit\" \"\"\n\"unit_struct\" \"\""
+    );
+}
@@ -1,6 +1,8 @@
 use valve_kv::{
     kv::{KeyValue, KeyValueFile, Value},
-    parser::parse_input,
+    parser::{
+        parse_file, parse_file_spanned, parse_input, parse_input_spanned, Location, SpannedValue,
+    },
 };
 
 #[test]
@@ -165,3 +167,73 @@ fn parse_imports() {
         }
     )
 }
+
+#[test]
+fn parse_spanned() {
+    let input = "#base \"a.kv\"\n\"key\"\n{\n\t\"inner\" \"value\"\n}";
+
+    let file = parse_input_spanned(input).unwrap();
+
+    assert_eq!(file.to_file(), parse_input(input).unwrap());
+    assert_eq!(
+        file.imports[0].1.start,
+        Location {
+            offset: 0,
+            line: 1,
+            column: 1
+        }
+    );
+
+    let kv = &file.kvs[0];
+    assert_eq!(
+        kv.key_span.start,
+        Location {
+            offset: 13,
+            line: 2,
+            column: 1
+        }
+    );
+    assert_eq!(
+        kv.value_span.end,
+        Location {
+            offset: input.len(),
+            line: 5,
+            column: 2
+        }
+    );
+
+    match &kv.value {
+        SpannedValue::Section(section) => {
+            assert_eq!(
+                &input[section[0].value_span.start.offset..section[0].value_span.end.offset],
+                "\"value\""
+            );
+            assert_eq!(section[0].key_span.start.line, 4);
+            assert_eq!(section[0].key_span.start.column, 2);
+        }
+        SpannedValue::Value(_) => panic!("expected section"),
+    }
+}
+
+#[test]
+fn parse_file_spanned_bases() {
+    let path = "./tests/test_kvs/rooted/abilities.kv";
+
+    let (file, bases) = parse_file_spanned(path).unwrap();
+
+    assert_eq!(file.imports[0].0, "base_abilities.kv");
+    assert_eq!(file.kvs[0].key_span.start.line, 3);
+
+    let mut kvs = file.to_file().kvs;
+    kvs.extend(bases);
+
+    assert_eq!(kvs, parse_file(path).unwrap());
+}
+
+#[test]
+fn escaped_quote_at_end() {
+    let kvs = parse_input(r#""\"key\"" "\"\"""#).unwrap().kvs;
+
+    assert_eq!(kvs[0].key, r#"\"key\""#);
+    assert_eq!(kvs[0].value, Value::Value(r#"\"\""#.to_string()));
+}
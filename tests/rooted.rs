@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use valve_kv::{
+    deserializer::{from_file_rooted, from_str_rooted},
+    error::Error,
+    serializer::to_string_rooted,
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Ability {
+    #[serde(rename = "AbilityCooldown")]
+    cooldown: u32,
+}
+
+type Abilities = BTreeMap<String, Ability>;
+
+#[test]
+fn rooted_de() {
+    let input = r#"
+    "DOTAAbilities"
+    {
+        "blink" { "AbilityCooldown" "15" }
+    }
+    "#;
+
+    let res: Abilities = from_str_rooted(input, None).unwrap();
+    assert_eq!(res["blink"], Ability { cooldown: 15 });
+
+    let res: Abilities = from_str_rooted(input, Some("dotaabilities")).unwrap();
+    assert_eq!(res.len(), 1);
+
+    assert!(matches!(
+        from_str_rooted::<Abilities>(input, Some("lang")),
+        Err(Error::UnexpectedRootError(root)) if root == "DOTAAbilities"
+    ));
+}
+
+#[test]
+fn rooted_errors() {
+    assert!(matches!(
+        from_str_rooted::<Abilities>(r#""a" {} "b" {}"#, None),
+        Err(Error::ExpectedSingleRootError(2))
+    ));
+    assert!(matches!(
+        from_str_rooted::<Abilities>("", None),
+        Err(Error::ExpectedSingleRootError(0))
+    ));
+
+    let input = "\"DOTAAbilities\"\n{\n\t\"blink\" { \"AbilityCooldown\" \"soon\" }\n}";
+    let err = from_str_rooted::<Abilities>(input, None).unwrap_err();
+
+    assert_eq!(
+        err.path().unwrap().to_string(),
+        "DOTAAbilities/blink/AbilityCooldown"
+    );
+    assert_eq!(err.location().unwrap().line, 3);
+}
+
+#[test]
+fn rooted_file_de() {
+    let res: Abilities =
+        from_file_rooted("tests/test_kvs/rooted/abilities.kv", Some("DOTAAbilities")).unwrap();
+
+    assert_eq!(res.keys().collect::<Vec<_>>(), vec!["blink", "tango"]);
+    assert_eq!(res["tango"], Ability { cooldown: 0 });
+}
+
+#[test]
+fn rooted_ser() {
+    let mut abilities = Abilities::new();
+    abilities.insert("blink".to_string(), Ability { cooldown: 15 });
+
+    let res = to_string_rooted(&abilities, "DOTAAbilities").unwrap();
+
+    assert_eq!(
+        res,
+        "\"DOTAAbilities\" \n{\n  \"blink\" \n  {\n    \"AbilityCooldown\" \"15\"\n  }\n}"
+    );
+    assert_eq!(
+        from_str_rooted::<Abilities>(&res, Some("DOTAAbilities")).unwrap(),
+        abilities
+    );
+}
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use valve_kv::{
+    deserializer::{from_str, from_str_with_options, DeserializerOptions, ScalarMode},
+    error::Error,
+};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Unit {
+    speed: f32,
+    armor: i32,
+    flags: u32,
+    level: u8,
+    hidden: bool,
+    ranged: bool,
+}
+
+fn options(scalars: ScalarMode) -> DeserializerOptions {
+    DeserializerOptions {
+        scalars,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn lenient_scalars() {
+    let input = r#"
+    "speed" "1.5f"
+    "armor" " -42 "
+    "flags" "0x1F"
+    "level" "12abc"
+    "hidden" "yes"
+    "ranged" "FALSE"
+    "#;
+
+    assert!(from_str::<Unit>(input).is_err());
+
+    let res: Unit = from_str_with_options(input, options(ScalarMode::Lenient)).unwrap();
+
+    assert_eq!(
+        res,
+        Unit {
+            speed: 1.5,
+            armor: -42,
+            flags: 31,
+            level: 12,
+            hidden: true,
+            ranged: false,
+        }
+    );
+}
+
+#[test]
+fn lenient_edge_cases() {
+    let input = r#"
+    "0" "abc"
+    "1" ".5e2x"
+    "2" "-3.e"
+    "3" "+7.25"
+    "#;
+
+    let res: Vec<f64> = from_str_with_options(input, options(ScalarMode::Lenient)).unwrap();
+    assert_eq!(res, vec![0.0, 50.0, -3.0, 7.25]);
+
+    let res: Vec<bool> =
+        from_str_with_options(r#""0" "2" "1" "0" "2" """#, options(ScalarMode::Lenient)).unwrap();
+    assert_eq!(res, vec![true, false, false]);
+
+    // engine parsing doesn't wrap around
+    let err =
+        from_str_with_options::<Vec<u8>>(r#""0" "300""#, options(ScalarMode::Lenient)).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        Error::InvalidScalarError { text, expected: "u8" } if text == "300"
+    ));
+}
+
+#[test]
+fn strict_scalars() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        value: f32,
+    }
+
+    let strict = || options(ScalarMode::Strict);
+
+    assert_eq!(
+        from_str_with_options::<Test>(r#""value" "-1.5e3""#, strict()).unwrap(),
+        Test { value: -1500.0 }
+    );
+
+    // str::parse accepts these
+    assert!(from_str::<Test>(r#""value" "inf""#).is_ok());
+    let err = from_str_with_options::<Test>(r#""value" "inf""#, strict()).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        Error::InvalidScalarError { text, expected: "f32" } if text == "inf"
+    ));
+
+    let err = from_str_with_options::<Vec<i32>>(r#""0" "12abc""#, strict()).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        Error::InvalidScalarError { text, expected: "i32" } if text == "12abc"
+    ));
+
+    let err = from_str_with_options::<Vec<bool>>(r#""0" "true""#, strict()).unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        Error::InvalidScalarError { text, expected: "bool" } if text == "true"
+    ));
+}
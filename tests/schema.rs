@@ -0,0 +1,184 @@
+use valve_kv::{
+    kv::{KeyPath, Value},
+    parser::parse_input,
+    schema::{Schema, SchemaType, Violation, ViolationKind},
+};
+
+const SCHEMA: &str = r#"
+"type" "section"
+"closed" "1"
+"children"
+{
+    "DOTAAbilities"
+    {
+        "type" "section"
+        "required" "1"
+        "children"
+        {
+            "Version" { "type" "int" }
+        }
+        "other"
+        {
+            "type" "section"
+            "children"
+            {
+                "AbilityCooldown" { "type" "float" "min" "0" "max" "300" "required" "1" }
+                "AbilityType" { "type" "enum" "values" { "0" "DOTA_ABILITY_TYPE_BASIC" "1" "DOTA_ABILITY_TYPE_ULTIMATE" } }
+                "IsGrantedByShard" { "type" "bool" }
+                "precache" { "type" "section" "other" { "type" "string" "repeated" "1" } }
+            }
+        }
+    }
+}
+"#;
+
+fn value(input: &str) -> Value {
+    Value::Section(parse_input(input).unwrap().kvs)
+}
+
+fn violation(path: &str, kind: ViolationKind) -> Violation {
+    Violation {
+        path: KeyPath::from(path),
+        kind,
+    }
+}
+
+#[test]
+fn parse_schema() {
+    let schema: Schema = SCHEMA.parse().unwrap();
+
+    assert_eq!(schema.value_type, SchemaType::Section);
+    assert!(schema.closed);
+
+    let abilities = schema.child("DOTAAbilities").unwrap();
+    let ability = abilities.other.as_deref().unwrap();
+
+    assert!(abilities.required);
+    assert_eq!(ability.child("AbilityCooldown").unwrap().max, Some(300.0));
+    assert_eq!(ability.child("AbilityType").unwrap().values.len(), 2);
+}
+
+#[test]
+fn schema_kv_round_trip() {
+    let schema: Schema = SCHEMA.parse().unwrap();
+
+    assert_eq!(Schema::from_kvs(&schema.to_kvs()).unwrap(), schema);
+}
+
+#[test]
+fn invalid_schema() {
+    assert!(r#""type" "number""#.parse::<Schema>().is_err());
+    assert!(r#""required" "yes""#.parse::<Schema>().is_err());
+    assert!(r#""unknown" "1""#.parse::<Schema>().is_err());
+}
+
+#[test]
+fn validate_valid() {
+    let schema: Schema = SCHEMA.parse().unwrap();
+
+    let input = value(
+        r#"
+    "DOTAAbilities"
+    {
+        "Version" "1"
+        "axe_berserkers_call"
+        {
+            "AbilityCooldown" "17.5"
+            "AbilityType" "DOTA_ABILITY_TYPE_BASIC"
+            "precache"
+            {
+                "soundfile" "a.vsndevts"
+                "soundfile" "b.vsndevts"
+            }
+        }
+    }
+    "#,
+    );
+
+    assert_eq!(schema.validate(&input), vec![]);
+}
+
+#[test]
+fn validate_reports_every_violation() {
+    let schema: Schema = SCHEMA.parse().unwrap();
+
+    let input = value(
+        r#"
+    "DOTAAbilities"
+    {
+        "Version" "one"
+        "axe_berserkers_call"
+        {
+            "AbilityCooldown" "-1"
+            "AbilityType" "DOTA_ABILITY_TYPE_HIDDEN"
+            "IsGrantedByShard" "yes"
+            "IsGrantedByShard" "1"
+            "precache" "none"
+        }
+        "axe_culling_blade"
+        {
+            "AbilityCooldown" { }
+        }
+        "axe_battle_hunger"
+        {
+        }
+    }
+    "Extra" "1"
+    "#,
+    );
+
+    assert_eq!(
+        schema.validate(&input),
+        vec![
+            violation(
+                "DOTAAbilities/Version",
+                ViolationKind::InvalidType(SchemaType::Int)
+            ),
+            violation(
+                "DOTAAbilities/axe_berserkers_call/AbilityCooldown",
+                ViolationKind::OutOfRange
+            ),
+            violation(
+                "DOTAAbilities/axe_berserkers_call/AbilityType",
+                ViolationKind::NotInEnum
+            ),
+            violation(
+                "DOTAAbilities/axe_berserkers_call/IsGrantedByShard",
+                ViolationKind::InvalidType(SchemaType::Bool)
+            ),
+            violation(
+                "DOTAAbilities/axe_berserkers_call/IsGrantedByShard",
+                ViolationKind::DuplicateKey
+            ),
+            violation(
+                "DOTAAbilities/axe_berserkers_call/precache",
+                ViolationKind::ExpectedSection
+            ),
+            violation(
+                "DOTAAbilities/axe_culling_blade/AbilityCooldown",
+                ViolationKind::ExpectedValue
+            ),
+            violation(
+                "DOTAAbilities/axe_battle_hunger/AbilityCooldown",
+                ViolationKind::MissingKey
+            ),
+            violation("Extra", ViolationKind::UnexpectedKey),
+        ]
+    );
+}
+
+#[test]
+fn validate_kvs() {
+    let schema: Schema = SCHEMA.parse().unwrap();
+
+    let violations = schema.validate_kvs(&[]);
+
+    assert_eq!(
+        violations,
+        vec![violation("DOTAAbilities", ViolationKind::MissingKey)]
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "DOTAAbilities: missing required key"
+    );
+}
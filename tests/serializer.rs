@@ -162,3 +162,13 @@ fn enum_ser() {
 
     assert_eq!(res, expected);
 }
+
+#[test]
+fn nested_seq_ser() {
+    let res = to_string(&vec![vec![1, 2], vec![3]]).unwrap();
+
+    assert_eq!(
+        res,
+        "{\n  \"0\" \n  {\n    \"0\" \"1\"\n    \"1\" \"2\"\n  }\n  \"1\" \n  {\n    \"0\" \"3\"\n  }\n}"
+    );
+}
@@ -0,0 +1,137 @@
+use valve_kv::{
+    kv::{KeyValue, TypedKeyValue, TypedValue, Value, ValueType},
+    parser::parse_input,
+    serializer::to_string,
+};
+
+#[test]
+fn infer_typed() {
+    assert_eq!(TypedValue::infer("42"), TypedValue::Int(42));
+    assert_eq!(TypedValue::infer("-7"), TypedValue::Int(-7));
+    assert_eq!(TypedValue::infer("1.5"), TypedValue::Float(1.5));
+    assert_eq!(
+        TypedValue::infer("76561197960287930"),
+        TypedValue::Uint64(76561197960287930)
+    );
+    assert_eq!(
+        TypedValue::infer("hello"),
+        TypedValue::String("hello".to_string())
+    );
+
+    // these would not print back to the same text
+    assert_eq!(
+        TypedValue::infer("007"),
+        TypedValue::String("007".to_string())
+    );
+    assert_eq!(
+        TypedValue::infer("1.50"),
+        TypedValue::String("1.50".to_string())
+    );
+    for text in ["inf", "-inf", "nan", "NaN"] {
+        assert_eq!(
+            TypedValue::infer(text),
+            TypedValue::String(text.to_string())
+        );
+    }
+}
+
+#[test]
+fn parse_typed() {
+    assert_eq!(
+        TypedValue::parse("255 128 0 255", ValueType::Color).unwrap(),
+        TypedValue::Color([255, 128, 0, 255])
+    );
+    assert_eq!(
+        TypedValue::parse("12", ValueType::WString).unwrap(),
+        TypedValue::WString("12".to_string())
+    );
+    assert!(TypedValue::parse("255 128 0", ValueType::Color).is_err());
+    assert!(TypedValue::parse("1.5", ValueType::Int).is_err());
+}
+
+#[test]
+fn typed_text_round_trip() {
+    let values = vec![
+        TypedValue::Int(i32::MIN),
+        TypedValue::Float(0.1),
+        TypedValue::Float(f32::MAX),
+        TypedValue::Pointer(0xdeadbeef),
+        TypedValue::Color([1, 2, 3, 4]),
+        TypedValue::Uint64(u64::MAX),
+        TypedValue::WString("text".to_string()),
+    ];
+
+    for value in values {
+        let text = value.to_text().unwrap();
+        let value_type = value.value_type().unwrap();
+
+        assert_eq!(TypedValue::parse(&text, value_type).unwrap(), value);
+    }
+}
+
+#[test]
+fn typed_kv_conversion() {
+    let kvs = parse_input(
+        r#"
+    "unit"
+    {
+        "name" "axe"
+        "level" "25"
+        "speed" "1.7"
+    }
+    "#,
+    )
+    .unwrap()
+    .kvs;
+
+    let typed: Vec<TypedKeyValue> = kvs.iter().map(TypedKeyValue::from).collect();
+
+    assert_eq!(
+        typed[0].value,
+        TypedValue::Section(vec![
+            TypedKeyValue {
+                key: "name".to_string(),
+                value: TypedValue::String("axe".to_string())
+            },
+            TypedKeyValue {
+                key: "level".to_string(),
+                value: TypedValue::Int(25)
+            },
+            TypedKeyValue {
+                key: "speed".to_string(),
+                value: TypedValue::Float(1.7)
+            },
+        ])
+    );
+
+    let back: Vec<KeyValue> = typed.iter().map(KeyValue::from).collect();
+
+    assert_eq!(back, kvs);
+    assert_eq!(
+        Value::from(&TypedValue::Color([0, 0, 0, 255])),
+        Value::Value("0 0 0 255".to_string())
+    );
+}
+
+#[test]
+fn typed_ser() {
+    let value = TypedValue::Section(vec![TypedKeyValue {
+        key: "color".to_string(),
+        value: TypedValue::Color([255, 0, 0, 255]),
+    }]);
+
+    assert_eq!(
+        to_string(&value).unwrap(),
+        "{\n  \"color\" \"255 0 0 255\"\n}"
+    );
+}
+
+#[test]
+fn typed_float_ser() {
+    let value = TypedValue::Section(vec![TypedKeyValue {
+        key: "speed".to_string(),
+        value: TypedValue::Float(1.7),
+    }]);
+
+    assert_eq!(to_string(&value).unwrap(), "{\n  \"speed\" \"1.7\"\n}");
+}
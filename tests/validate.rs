@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use valve_kv::{
+    deserializer::{from_str_validating, DeserializerOptions},
+    error::Error,
+};
+
+#[derive(Debug, Deserialize, PartialEq, Default)]
+#[serde(default)]
+struct Ability {
+    #[serde(rename = "AbilityCooldown")]
+    cooldown: u32,
+    #[serde(rename = "AbilityManaCost")]
+    mana_cost: u32,
+    levels: Vec<u32>,
+}
+
+fn paths(errors: &[Error]) -> Vec<String> {
+    errors
+        .iter()
+        .map(|e| e.path().map(|p| p.to_string()).unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn validate_defaults() {
+    let input = r#"
+    "AbilityCooldown" "soon"
+    "AbilityManaCost" "50"
+    "levels" { "0" "1" "1" "two" "2" "3" }
+    "#;
+
+    let (value, errors) = from_str_validating::<Ability>(input, Default::default()).unwrap();
+
+    assert_eq!(
+        value,
+        Some(Ability {
+            cooldown: 0,
+            mana_cost: 50,
+            levels: vec![1, 3],
+        })
+    );
+    assert_eq!(paths(&errors), vec!["AbilityCooldown", "levels/1"]);
+    assert!(matches!(errors[0].inner(), Error::ExpectedValueError));
+    assert_eq!(errors[0].location().unwrap().line, 2);
+}
+
+#[test]
+fn validate_skips_entries() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        cost: u32,
+    }
+
+    let input = r#"
+    "blink" { "cost" "2250" }
+    "broken" { "cost" "a lot" }
+    "empty" {}
+    "tango" { "cost" "90" }
+    "#;
+
+    let (value, errors) =
+        from_str_validating::<BTreeMap<String, Item>>(input, Default::default()).unwrap();
+
+    let value = value.unwrap();
+    assert_eq!(value.keys().collect::<Vec<_>>(), vec!["blink", "tango"]);
+
+    // "broken" is only missing the invalid cost, so it isn't reported again
+    assert_eq!(paths(&errors), vec!["broken/cost", "empty"]);
+    assert!(matches!(
+        errors[1].inner(),
+        Error::MissingFieldError("cost")
+    ));
+}
+
+#[test]
+fn validate_duplicates() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        a: String,
+    }
+
+    let (value, errors) =
+        from_str_validating::<Test>(r#""a" "1" "a" "2" "a" "3""#, Default::default()).unwrap();
+
+    assert_eq!(value.unwrap().a, "1");
+    assert_eq!(paths(&errors), vec!["a"]);
+    assert_eq!(errors[0].location().unwrap().column, 9);
+}
+
+#[test]
+fn validate_root_error() {
+    let (value, errors) =
+        from_str_validating::<Vec<u32>>(r#""0" "1" "2" "3""#, Default::default()).unwrap();
+
+    assert_eq!(value, None);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::SequenceGapError(1)));
+
+    assert!(from_str_validating::<Vec<u32>>("\"unclosed", Default::default()).is_err());
+}
+
+#[test]
+fn validate_missing_fields() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        a: u32,
+        b: u32,
+    }
+
+    let (value, errors) =
+        from_str_validating::<Test>(r#""a" "x" "b" "1""#, Default::default()).unwrap();
+
+    assert_eq!(value, None);
+    assert_eq!(paths(&errors), vec!["a"]);
+
+    // a field that is really missing is still reported
+    let (value, errors) = from_str_validating::<Test>(r#""b" "x""#, Default::default()).unwrap();
+
+    assert_eq!(value, None);
+    assert_eq!(paths(&errors), vec!["b", ""]);
+    assert!(matches!(&errors[1], Error::MissingFieldError("a")));
+}
+
+#[test]
+fn validate_max_errors() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        #[serde(rename = "ItemCost")]
+        cost: u32,
+    }
+
+    let input = r#"
+    "a" { "ItemCost" "x" }
+    "b" { "ItemCost" "y" }
+    "c" { "ItemCost" "z" }
+    "#;
+
+    // the renamed field is still recognised as the skipped one
+    let (value, errors) =
+        from_str_validating::<BTreeMap<String, Item>>(input, Default::default()).unwrap();
+    assert!(value.unwrap().is_empty());
+    assert_eq!(
+        paths(&errors),
+        vec!["a/ItemCost", "b/ItemCost", "c/ItemCost"]
+    );
+
+    let options = DeserializerOptions {
+        max_errors: 2,
+        ..Default::default()
+    };
+
+    let (value, errors) = from_str_validating::<BTreeMap<String, Item>>(input, options).unwrap();
+    assert!(value.is_none());
+    assert_eq!(paths(&errors), vec!["a/ItemCost", "b/ItemCost"]);
+}
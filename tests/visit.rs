@@ -0,0 +1,141 @@
+use valve_kv::{
+    kv::{depth_first, walk, walk_mut, KeyPath, KeyValue, Value, Visit, VisitMut},
+    parser::parse_input,
+};
+
+const INPUT: &str = r#"
+"DOTAAbilities"
+{
+    "Version" "1"
+    "axe_berserkers_call"
+    {
+        "AbilityCooldown" "17"
+        "AbilityValues"
+        {
+            "radius" "300"
+        }
+    }
+}
+"#;
+
+fn kvs() -> Vec<KeyValue> {
+    parse_input(INPUT).unwrap().kvs
+}
+
+#[test]
+fn visit_paths() {
+    #[derive(Default)]
+    struct Collector {
+        values: Vec<String>,
+        sections: Vec<String>,
+    }
+
+    impl Visit for Collector {
+        fn visit_value(&mut self, path: &KeyPath, _kv: &KeyValue) {
+            self.values.push(path.to_string());
+        }
+
+        fn leave_section(&mut self, path: &KeyPath, _kv: &KeyValue) {
+            self.sections.push(path.to_string());
+        }
+    }
+
+    let mut collector = Collector::default();
+    walk(&mut collector, &kvs());
+
+    assert_eq!(
+        collector.values,
+        vec![
+            "DOTAAbilities/Version",
+            "DOTAAbilities/axe_berserkers_call/AbilityCooldown",
+            "DOTAAbilities/axe_berserkers_call/AbilityValues/radius",
+        ]
+    );
+    assert_eq!(
+        collector.sections,
+        vec![
+            "DOTAAbilities/axe_berserkers_call/AbilityValues",
+            "DOTAAbilities/axe_berserkers_call",
+            "DOTAAbilities",
+        ]
+    );
+}
+
+#[test]
+fn visit_skip_section() {
+    struct Counter(usize);
+
+    impl Visit for Counter {
+        fn visit_value(&mut self, _path: &KeyPath, _kv: &KeyValue) {
+            self.0 += 1;
+        }
+
+        fn enter_section(&mut self, _path: &KeyPath, kv: &KeyValue) -> bool {
+            kv.key != "AbilityValues"
+        }
+    }
+
+    let mut counter = Counter(0);
+    walk(&mut counter, &kvs());
+
+    assert_eq!(counter.0, 2);
+}
+
+#[test]
+fn visit_mut_rename() {
+    struct Renamer;
+
+    impl VisitMut for Renamer {
+        fn visit_value_mut(&mut self, path: &KeyPath, kv: &mut KeyValue) {
+            if path.to_string() == "DOTAAbilities/abyssal_call/AbilityCooldown" {
+                kv.value = Value::Value("12".to_string());
+            }
+        }
+
+        fn enter_section_mut(&mut self, _path: &KeyPath, kv: &mut KeyValue) -> bool {
+            if kv.key == "axe_berserkers_call" {
+                kv.key = "abyssal_call".to_string();
+            }
+
+            true
+        }
+    }
+
+    let mut kvs = kvs();
+    walk_mut(&mut Renamer, &mut kvs);
+
+    let expected = parse_input(
+        &INPUT
+            .replace("axe_berserkers_call", "abyssal_call")
+            .replace("17", "12"),
+    )
+    .unwrap()
+    .kvs;
+
+    assert_eq!(kvs, expected);
+}
+
+#[test]
+fn depth_first_iter() {
+    let kvs = kvs();
+
+    let paths: Vec<String> = depth_first(&kvs)
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "DOTAAbilities",
+            "DOTAAbilities/Version",
+            "DOTAAbilities/axe_berserkers_call",
+            "DOTAAbilities/axe_berserkers_call/AbilityCooldown",
+            "DOTAAbilities/axe_berserkers_call/AbilityValues",
+            "DOTAAbilities/axe_berserkers_call/AbilityValues/radius",
+        ]
+    );
+
+    let (_, radius) = depth_first(&kvs).last().unwrap();
+
+    assert_eq!(radius.value, Value::Value("300".to_string()));
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+use valve_kv::{
+    deserializer::{
+        from_str, from_str_validating, from_str_with_options, DeserializerOptions, ScalarMode,
+        VariantMatching,
+    },
+    serializer::to_file,
+    with::{pipe_separated, space_separated},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Entity {
+    #[serde(with = "space_separated")]
+    origin: [f32; 3],
+    #[serde(with = "space_separated")]
+    color: (u8, u8, u8, u8),
+    #[serde(with = "space_separated")]
+    levels: Vec<u32>,
+}
+
+#[test]
+fn space_separated_de() {
+    let input = r#"
+    "origin" "128 -64 32.5"
+    "color" "  255 128	0 255 "
+    "levels" "10 20 30 40"
+    "#;
+
+    let res = from_str::<Entity>(input).unwrap();
+
+    assert_eq!(
+        res,
+        Entity {
+            origin: [128.0, -64.0, 32.5],
+            color: (255, 128, 0, 255),
+            levels: vec![10, 20, 30, 40],
+        }
+    );
+}
+
+#[test]
+fn space_separated_ser() {
+    let entity = Entity {
+        origin: [1.5, 0.0, -2.0],
+        color: (0, 0, 0, 255),
+        levels: vec![],
+    };
+
+    let res = to_file(&entity).unwrap();
+
+    assert_eq!(
+        res,
+        "\"origin\" \"1.5 0 -2\"\n\"color\" \"0 0 0 255\"\n\"levels\" \"\""
+    );
+    assert_eq!(from_str::<Entity>(&res).unwrap(), entity);
+}
+
+#[test]
+fn space_separated_errors() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Test {
+        #[serde(with = "space_separated")]
+        origin: [f32; 3],
+    }
+
+    assert!(from_str::<Test>(r#""origin" "1 2""#).is_err());
+    assert!(from_str::<Test>(r#""origin" "1 2 x""#).is_err());
+
+    let err = from_str::<Test>(r#""origin" { "0" "1" }"#).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "origin");
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Behavior {
+    #[serde(rename = "DOTA_ABILITY_BEHAVIOR_NO_TARGET")]
+    NoTarget,
+    #[serde(rename = "DOTA_ABILITY_BEHAVIOR_IMMEDIATE")]
+    Immediate,
+    #[serde(rename = "DOTA_ABILITY_BEHAVIOR_HIDDEN")]
+    Hidden,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Ability {
+    #[serde(rename = "AbilityBehavior", with = "pipe_separated")]
+    behavior: HashSet<Behavior>,
+    #[serde(with = "pipe_separated")]
+    list: Vec<Behavior>,
+    #[serde(with = "pipe_separated")]
+    empty: BTreeSet<Behavior>,
+}
+
+#[test]
+fn pipe_separated_de() {
+    let input = r#"
+    "AbilityBehavior" "DOTA_ABILITY_BEHAVIOR_NO_TARGET | DOTA_ABILITY_BEHAVIOR_IMMEDIATE|DOTA_ABILITY_BEHAVIOR_HIDDEN"
+    "list" "DOTA_ABILITY_BEHAVIOR_HIDDEN | DOTA_ABILITY_BEHAVIOR_NO_TARGET"
+    "empty" ""
+    "#;
+
+    let res = from_str::<Ability>(input).unwrap();
+
+    assert_eq!(
+        res.behavior,
+        HashSet::from([Behavior::NoTarget, Behavior::Immediate, Behavior::Hidden])
+    );
+    assert_eq!(res.list, vec![Behavior::Hidden, Behavior::NoTarget]);
+    assert!(res.empty.is_empty());
+
+    let unknown = r#""AbilityBehavior" "DOTA_ABILITY_BEHAVIOR_PASSIVE" "list" "" "empty" """#;
+    assert!(from_str::<Ability>(unknown).is_err());
+}
+
+#[test]
+fn pipe_separated_ser() {
+    let ability = Ability {
+        behavior: HashSet::from([Behavior::Immediate, Behavior::Hidden, Behavior::NoTarget]),
+        list: vec![Behavior::NoTarget, Behavior::Hidden, Behavior::NoTarget],
+        empty: BTreeSet::new(),
+    };
+
+    let res = to_file(&ability).unwrap();
+
+    assert_eq!(
+        res,
+        "\"AbilityBehavior\" \"DOTA_ABILITY_BEHAVIOR_HIDDEN | DOTA_ABILITY_BEHAVIOR_IMMEDIATE | DOTA_ABILITY_BEHAVIOR_NO_TARGET\"\n\"list\" \"DOTA_ABILITY_BEHAVIOR_HIDDEN | DOTA_ABILITY_BEHAVIOR_NO_TARGET\"\n\"empty\" \"\""
+    );
+}
+
+#[test]
+fn space_separated_ser_errors() {
+    #[derive(Debug, Serialize)]
+    struct Optional {
+        #[serde(with = "space_separated")]
+        origin: Option<[f32; 3]>,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Scalar {
+        #[serde(with = "space_separated")]
+        scale: f32,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Nested {
+        #[serde(with = "space_separated")]
+        points: Vec<[f32; 2]>,
+    }
+
+    let res = to_file(&Optional {
+        origin: Some([1.0, 2.0, 3.0]),
+    })
+    .unwrap();
+    assert_eq!(res, "\"origin\" \"1 2 3\"");
+
+    assert!(to_file(&Optional { origin: None }).is_err());
+    assert!(to_file(&Scalar { scale: 1.5 }).is_err());
+    assert!(to_file(&Nested {
+        points: vec![[0.0, 1.0]]
+    })
+    .is_err());
+}
+
+#[test]
+fn space_separated_options() {
+    #[derive(Debug, Deserialize, PartialEq, Default)]
+    #[serde(default)]
+    struct Test {
+        #[serde(with = "space_separated")]
+        origin: [f32; 3],
+        #[serde(with = "space_separated")]
+        levels: Vec<u32>,
+    }
+
+    let options = DeserializerOptions {
+        scalars: ScalarMode::Lenient,
+        ..Default::default()
+    };
+
+    let res = from_str_with_options::<Test>(
+        r#""origin" "1.5f 2 -3" "levels" " 1 2 3 ""#,
+        options.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.origin, [1.5, 2.0, -3.0]);
+    assert_eq!(res.levels, vec![1, 2, 3]);
+
+    let err = from_str::<Test>(r#""origin" "1 2 x""#).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "origin/2");
+    assert_eq!(err.location().unwrap().line, 1);
+
+    // a bad token skips the whole value
+    let (value, errors) =
+        from_str_validating::<Test>(r#""origin" "1 x 3" "levels" "4""#, Default::default())
+            .unwrap();
+    assert_eq!(
+        value,
+        Some(Test {
+            origin: [0.0; 3],
+            levels: vec![4],
+        })
+    );
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn pipe_separated_options() {
+    #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+    enum Flag {
+        NoTarget,
+        Immediate,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Test {
+        #[serde(with = "pipe_separated")]
+        flags: Vec<Flag>,
+    }
+
+    let options = DeserializerOptions {
+        variants: VariantMatching::IgnoreCaseAndUnderscores,
+        variant_prefixes: vec!["DOTA_ABILITY_BEHAVIOR_".to_string()],
+        ..Default::default()
+    };
+
+    let input = r#""flags" "DOTA_ABILITY_BEHAVIOR_NO_TARGET | DOTA_ABILITY_BEHAVIOR_IMMEDIATE""#;
+
+    let res = from_str_with_options::<Test>(input, options).unwrap();
+    assert_eq!(res.flags, vec![Flag::NoTarget, Flag::Immediate]);
+
+    assert!(from_str::<Test>(input).is_err());
+
+    let options = DeserializerOptions {
+        variants: VariantMatching::IgnoreCase,
+        ..Default::default()
+    };
+
+    let res = from_str_with_options::<Test>(r#""flags" "immediate|notarget""#, options).unwrap();
+    assert_eq!(res.flags, vec![Flag::Immediate, Flag::NoTarget]);
+}
+
+#[test]
+fn separated_json() {
+    let json = r#"{"origin":"1 2 3","color":"0 0 0 255","levels":""}"#;
+    let entity: Entity = serde_json::from_str(json).unwrap();
+
+    assert_eq!(entity.origin, [1.0, 2.0, 3.0]);
+    assert_eq!(entity.color, (0, 0, 0, 255));
+    assert!(entity.levels.is_empty());
+}